@@ -0,0 +1,118 @@
+use colorful::{Color, Colorful};
+use console::{measure_text_width, pad_str, truncate_str, Alignment};
+
+use crate::{
+    models::types::{PerformIcmpResponse, PerformIcmpResponseResultsItem},
+    options::{JobKind, Opts},
+};
+
+use super::{
+    ping_display::node_argument,
+    theme::{result_colour, Printer},
+};
+
+const COUNTRY_WIDTH: usize = 16;
+const CITY_WIDTH: usize = 14;
+const ISP_WIDTH: usize = 18;
+const IP_WIDTH: usize = 15;
+/// Wide enough for `xmt/rcv/%loss = 10/10/100.0%`.
+const PACKETS_WIDTH: usize = 30;
+
+/// Prints one line for each endpoint a node pinged, fping style, with its
+/// location, packet counts and RTTs in fixed width columns.
+pub fn print_compact_lines(
+    pb: &Printer,
+    config: &'static Opts,
+    payload_size: usize,
+    response: &PerformIcmpResponse,
+) {
+    let JobKind::Icmp(ref opts) = config.job else {
+        return;
+    };
+
+    let node = response.node_info.as_ref();
+    let country = node.and_then(|n| n.country());
+    let location = [
+        country
+            .as_ref()
+            .map_or("  ".to_string(), |c| c.emoji().to_string()),
+        cell(
+            &country.as_ref().map_or_else(
+                || node.map_or("Unknown".to_string(), |n| n.country_code.clone()),
+                |c| c.iso_short_name().to_string(),
+            ),
+            COUNTRY_WIDTH,
+        ),
+        cell(node.map_or("", |n| n.city.as_str()), CITY_WIDTH),
+        cell(node.map_or("", |n| n.isp.as_str()), ISP_WIDTH),
+    ]
+    .join(" ");
+
+    let endpoint_width = opts
+        .endpoints
+        .iter()
+        .map(|e| e.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    for item in &response.results {
+        // Endpoints and payload sizes only need telling apart when there's
+        // more than one.
+        let mut line = String::new();
+        if opts.endpoints.len() > 1 {
+            line += &format!("{} ", cell(&item.endpoint, endpoint_width));
+        }
+        if opts.sweep.is_some() {
+            line += &format!("{:>5} B ", payload_size);
+        }
+        line += &location;
+        line += " ";
+        line += &statistics(item);
+        if let Some(node) = node {
+            line += &format!("  {}", node_argument(node));
+        }
+
+        let colour = match (&item.error, &item.result) {
+            (None, Some(r)) if r.packet_loss < 1.0 => {
+                result_colour(config, Some(r.avg), r.packet_loss)
+            }
+            _ => Color::Red,
+        };
+        pb.println(format!("{}", line.trim_end().color(colour)));
+    }
+}
+
+fn statistics(item: &PerformIcmpResponseResultsItem) -> String {
+    let ip_address = item.result.as_ref().map_or("", |r| r.ip_address.as_str());
+    let ip_address = cell(ip_address, IP_WIDTH);
+
+    match (&item.error, &item.result) {
+        (Some(error), _) => format!("{ip_address} error: {error}"),
+        (None, Some(r)) => {
+            let packets = format!(
+                "xmt/rcv/%loss = {}/{}/{:.1}%",
+                r.packets_sent,
+                r.packets_recv,
+                r.packet_loss * 100.0
+            );
+            if r.packet_loss >= 1.0 {
+                return format!("{ip_address} {packets}");
+            }
+            format!(
+                "{ip_address} {:PACKETS_WIDTH$} min/avg/max = {:.1}/{:.1}/{:.1} ms",
+                packets, r.min, r.avg, r.max
+            )
+        }
+        (None, None) => format!("{ip_address} no result"),
+    }
+}
+
+/// Text cut or padded to exactly `width` columns, measured as displayed so
+/// wide characters don't push the columns after them out of line.
+fn cell(text: &str, width: usize) -> String {
+    if measure_text_width(text) > width {
+        truncate_str(text, width, "…").into_owned()
+    } else {
+        pad_str(text, width, Alignment::Left, None).into_owned()
+    }
+}
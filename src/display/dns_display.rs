@@ -0,0 +1,71 @@
+use crate::{
+    models::{
+        types::{PerformDnsResponseResultsItem, PerformDnsResponseResultsItemResult},
+        NodeInfo,
+    },
+    options::Opts,
+};
+use colorful::{Color, Colorful};
+
+use super::{
+    ping_display::{format_node_header, print_border, print_footer, sleep_if_enabled},
+    theme::Printer,
+};
+
+pub async fn display_dns_lookup(
+    pb: &Printer,
+    config: &'static Opts,
+    result: &PerformDnsResponseResultsItem,
+    jobres: &PerformDnsResponseResultsItemResult,
+    node_info: &NodeInfo,
+) {
+    let width = 80;
+    print_border(pb, width);
+
+    let servers = if jobres.dns_servers.is_empty() {
+        "default resolver".to_string()
+    } else {
+        jobres.dns_servers.join(", ")
+    };
+    pb.println(format!("│ DNS {} ({})", result.endpoint, servers));
+    format_node_header(pb, node_info);
+
+    let records = [
+        ("IP", &jobres.ips),
+        ("MX", &jobres.mx),
+        ("NS", &jobres.ns),
+        ("TXT", &jobres.txt),
+        ("SOA", &jobres.soa),
+        ("SRV", &jobres.srv),
+        ("TLSA", &jobres.tlsa),
+    ];
+
+    let mut answers = 0;
+    for (record_type, values) in records {
+        for value in values {
+            pb.println(format!("│ {:<5} {}", record_type, value));
+            answers += 1;
+        }
+        if !values.is_empty() {
+            sleep_if_enabled(config, 100).await;
+        }
+    }
+
+    pb.println("│");
+    pb.println(format!("│ --- {} lookup statistics ---", result.endpoint));
+    sleep_if_enabled(config, 250).await;
+
+    let summary = match result.duration {
+        Some(duration) => format!("│ {} answers received in {:.2} ms", answers, duration),
+        None => format!("│ {} answers received", answers),
+    };
+
+    if answers == 0 {
+        pb.println(format!("{}", summary.color(Color::Red)));
+    } else {
+        pb.println(summary);
+    }
+    sleep_if_enabled(config, 250).await;
+
+    print_footer(pb, width);
+}
@@ -0,0 +1,227 @@
+use colorful::{Color, Colorful};
+
+use crate::{
+    job::{JobData, JobResponse},
+    models::types::PerformIcmpResponseResultsItemResult,
+    options::{JobKind, Opts},
+};
+
+use super::{
+    ping_display::{print_border, print_footer},
+    theme::Printer,
+};
+
+/// Roughly how many bins to split the RTTs into.
+const BINS: usize = 10;
+/// How many columns the longest bar and the sparklines take up, unless the
+/// terminal is too narrow.
+const BAR_WIDTH: usize = 40;
+const MIN_BAR_WIDTH: usize = 10;
+/// The columns taken up by everything but the bars.
+const LABELS_WIDTH: usize = NAME_WIDTH + 36;
+const NAME_WIDTH: usize = 12;
+
+const BAR_EIGHTHS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// The nodes that replied from one region, or from all of them.
+struct Group<'a> {
+    name: String,
+    results: Vec<&'a PerformIcmpResponseResultsItemResult>,
+    failed: usize,
+}
+
+impl Group<'_> {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            results: vec![],
+            failed: 0,
+        }
+    }
+}
+
+/// Prints, for every endpoint, a histogram of node average RTTs for each
+/// region and overall, followed by a sparkline of how far each region's
+/// nodes spread.
+pub fn print_histograms(pb: &Printer, config: &'static Opts, responses: &[JobResponse]) {
+    let JobKind::Icmp(ref opts) = config.job else {
+        return;
+    };
+
+    for endpoint in &opts.endpoints {
+        let mut groups: Vec<Group> = config
+            .regions
+            .iter()
+            .map(|region| Group::new(region.name()))
+            .collect();
+        let mut all = Group::new("All".to_string());
+
+        for response in responses {
+            let JobData::Icmp { response: icmp, .. } = &response.data else {
+                continue;
+            };
+            let name = response.region.name();
+            let index = match groups.iter().position(|g| g.name == name) {
+                Some(index) => index,
+                None => {
+                    groups.push(Group::new(name));
+                    groups.len() - 1
+                }
+            };
+
+            for item in icmp.results.iter().filter(|i| i.endpoint == *endpoint) {
+                match item.result.as_ref().filter(|_| item.error.is_none()) {
+                    Some(result) if result.packet_loss < 1.0 => {
+                        groups[index].results.push(result);
+                        all.results.push(result);
+                    }
+                    _ => {
+                        groups[index].failed += 1;
+                        all.failed += 1;
+                    }
+                }
+            }
+        }
+
+        let slowest = all.results.iter().map(|r| r.avg).fold(0.0, f64::max);
+        let step = bin_step(slowest);
+        let bins = ((slowest / step).floor() as usize + 1).max(1);
+        let width = pb.fit(LABELS_WIDTH + BAR_WIDTH);
+        let bar_width = width.saturating_sub(LABELS_WIDTH).max(MIN_BAR_WIDTH);
+
+        print_border(pb, width);
+        pb.println(format!("│ HISTOGRAM {endpoint}"));
+        pb.println("│ ---");
+        for group in groups.iter().chain([&all]) {
+            print_histogram(pb, group, step, bins, bar_width);
+            pb.println("│");
+        }
+
+        pb.println("│ --- spread of each node's avg ± stddev");
+        for group in groups.iter().chain([&all]) {
+            print_sparkline(pb, group, step * bins as f64, bar_width);
+        }
+        pb.println(format!(
+            "│ {:NAME_WIDTH$} {:<half$}{:>half$}",
+            "",
+            "0 ms",
+            format!("{:.0} ms", step * bins as f64),
+            half = bar_width / 2
+        ));
+        print_footer(pb, width);
+        pb.println("");
+    }
+}
+
+fn print_histogram(pb: &Printer, group: &Group, step: f64, bins: usize, bar_width: usize) {
+    pb.println(format!(
+        "│ {} · {} nodes",
+        group.name,
+        group.results.len() + group.failed
+    ));
+    if group.results.is_empty() && group.failed == 0 {
+        pb.println("│   no results");
+        return;
+    }
+
+    let mut counts = vec![0; bins];
+    for result in &group.results {
+        counts[((result.avg / step) as usize).min(bins - 1)] += 1;
+    }
+    let tallest = counts.iter().copied().max().unwrap_or(0).max(group.failed);
+
+    for (bin, count) in counts.iter().enumerate() {
+        let low = step * bin as f64;
+        pb.println(format!(
+            "│   {:>5.0} - {:>5.0} ms  {} {}",
+            low,
+            low + step,
+            bar(*count, tallest, bar_width).color(Color::Cyan),
+            count
+        ));
+    }
+    if group.failed > 0 {
+        pb.println(format!(
+            "│   {:<16}  {} {}",
+            "failed",
+            bar(group.failed, tallest, bar_width).color(Color::Red),
+            group.failed
+        ));
+    }
+}
+
+fn print_sparkline(pb: &Printer, group: &Group, axis_max: f64, bar_width: usize) {
+    if group.results.is_empty() {
+        pb.println(format!(
+            "│ {:<NAME_WIDTH$} {:bar_width$} no replies",
+            group.name, ""
+        ));
+        return;
+    }
+
+    // Count how many nodes' avg ± stddev covers each column.
+    let mut coverage = vec![0usize; bar_width];
+    let column = |ms: f64| ((ms / axis_max * bar_width as f64) as usize).min(bar_width - 1);
+    for result in &group.results {
+        let (low, high) = (
+            column((result.avg - result.std_dev).max(0.0)),
+            column(result.avg + result.std_dev),
+        );
+        for covered in &mut coverage[low..=high] {
+            *covered += 1;
+        }
+    }
+    let deepest = coverage.iter().copied().max().unwrap_or(1);
+
+    let sparkline: String = coverage
+        .iter()
+        .map(|&count| match count {
+            0 => ' ',
+            _ => SPARK_LEVELS[(count * SPARK_LEVELS.len()).div_ceil(deepest) - 1],
+        })
+        .collect();
+
+    let min = group.results.iter().map(|r| r.min).fold(f64::MAX, f64::min);
+    let max = group.results.iter().map(|r| r.max).fold(0.0, f64::max);
+    let avg = group.results.iter().map(|r| r.avg).sum::<f64>() / group.results.len() as f64;
+    let std_dev = group.results.iter().map(|r| r.std_dev).sum::<f64>() / group.results.len() as f64;
+
+    pb.println(format!(
+        "│ {:<NAME_WIDTH$} {}  min/avg/max {:.1}/{:.1}/{:.1} ms ± {:.1}",
+        group.name,
+        sparkline.color(Color::Cyan),
+        min,
+        avg,
+        max,
+        std_dev
+    ));
+}
+
+/// A bar made of eighth blocks, scaled so the tallest count fills the width.
+fn bar(count: usize, tallest: usize, width: usize) -> String {
+    if count == 0 || tallest == 0 {
+        return String::new();
+    }
+
+    let eighths = (count * width * 8).div_ceil(tallest);
+    let mut bar = "█".repeat(eighths / 8);
+    let remainder = eighths % 8;
+    if remainder > 0 {
+        bar.push(BAR_EIGHTHS[remainder - 1]);
+    }
+    bar
+}
+
+/// A round bin width that splits RTTs up to the slowest into about [`BINS`]
+/// bins.
+fn bin_step(slowest: f64) -> f64 {
+    let raw = (slowest / BINS as f64).max(1.0);
+    let magnitude = 10f64.powf(raw.log10().floor());
+
+    [1.0, 2.0, 2.5, 5.0, 10.0]
+        .iter()
+        .map(|m| m * magnitude)
+        .find(|step| *step >= raw)
+        .unwrap_or(10.0 * magnitude)
+}
@@ -0,0 +1,188 @@
+use crate::{
+    models::{HlsDownload, HlsFragment, HlsPlaylist, HlsStream, HlsTimings, NodeInfo},
+    options::Opts,
+};
+use colorful::{Color, Colorful};
+
+use super::{
+    ping_display::{format_node_header, print_border, print_footer, sleep_if_enabled},
+    theme::Printer,
+};
+
+pub async fn display_hls_stream(
+    pb: &Printer,
+    config: &'static Opts,
+    endpoint: &str,
+    duration: Option<f64>,
+    stream: &HlsStream,
+    node_info: &NodeInfo,
+) {
+    let width = 80;
+    print_border(pb, width);
+    pb.println(format!("│ HLS {}", endpoint));
+    format_node_header(pb, node_info);
+
+    if let Some(master) = &stream.master {
+        pb.println(format!("│ {}", format_playlist(master)));
+        sleep_if_enabled(config, 100).await;
+    }
+
+    let rendition_count = stream.renditions.len();
+    for (index, rendition) in stream.renditions.iter().enumerate() {
+        let (branch, indent) = if index + 1 == rendition_count {
+            ("└──", "    ")
+        } else {
+            ("├──", "│   ")
+        };
+
+        pb.println(format!(
+            "│ {} {} {} @ {:.2} Mbps (target {:.0}s)",
+            branch,
+            rendition.playlist.file,
+            rendition.resolution,
+            rendition.bandwidth / 1_000_000.0,
+            rendition.target_duration_secs
+        ));
+        pb.println(format!(
+            "│ {}{}",
+            indent,
+            format_playlist_metrics(&rendition.playlist)
+        ));
+
+        let fragment_count = rendition.fragments.len();
+        for (index, fragment) in rendition.fragments.iter().enumerate() {
+            let branch = if index + 1 == fragment_count {
+                "└──"
+            } else {
+                "├──"
+            };
+            let line = format!("│ {}{} {}", indent, branch, format_fragment(fragment));
+            if fragment.download_ratio < 1.0 {
+                pb.println(format!("{}", line.color(Color::Red)));
+            } else {
+                pb.println(line);
+            }
+        }
+        sleep_if_enabled(config, 100).await;
+    }
+
+    let fragments: Vec<&HlsFragment> = stream
+        .renditions
+        .iter()
+        .flat_map(|r| r.fragments.iter())
+        .collect();
+
+    pb.println("│");
+    pb.println(format!("│ --- {} stream statistics ---", endpoint));
+    sleep_if_enabled(config, 250).await;
+
+    let summary = match duration {
+        Some(duration) => format!(
+            "│ {} renditions, {} fragments downloaded in {:.2} ms",
+            rendition_count,
+            fragments.len(),
+            duration
+        ),
+        None => format!(
+            "│ {} renditions, {} fragments downloaded",
+            rendition_count,
+            fragments.len()
+        ),
+    };
+
+    if fragments.is_empty() {
+        pb.println(format!("{}", summary.color(Color::Red)));
+    } else {
+        pb.println(summary);
+
+        let min_ratio = fragments
+            .iter()
+            .map(|f| f.download_ratio)
+            .fold(f64::INFINITY, f64::min);
+        let avg_ratio =
+            fragments.iter().map(|f| f.download_ratio).sum::<f64>() / fragments.len() as f64;
+        let ratio_line = format!(
+            "│ download ratio min/avg = {:.2}x/{:.2}x realtime",
+            min_ratio, avg_ratio
+        );
+        if min_ratio < 1.0 {
+            pb.println(format!("{}", ratio_line.color(Color::Red)));
+        } else {
+            pb.println(ratio_line);
+        }
+    }
+    sleep_if_enabled(config, 250).await;
+
+    print_footer(pb, width);
+}
+
+fn format_playlist(playlist: &HlsPlaylist) -> String {
+    format!("{} {}", playlist.file, format_playlist_metrics(playlist))
+}
+
+fn format_playlist_metrics(playlist: &HlsPlaylist) -> String {
+    let download = playlist
+        .download
+        .as_ref()
+        .map_or("no download metrics".to_string(), format_download);
+
+    match &playlist.timings {
+        Some(timings) => format!("{} [{}]", download, format_timings(timings)),
+        None => download,
+    }
+}
+
+/// Fragments are listed one per line, so only the time to first byte is shown
+/// rather than the full connection breakdown.
+fn format_fragment(fragment: &HlsFragment) -> String {
+    let mut line = format!(
+        "{} ({:.1}s)",
+        fragment.playlist.file, fragment.duration_secs
+    );
+    if let Some(download) = &fragment.playlist.download {
+        line.push_str(&format!(
+            " {} in {:.2} ms",
+            format_bytes(download.size),
+            download.time_ms
+        ));
+    }
+    if let Some(timings) = &fragment.playlist.timings {
+        line.push_str(&format!(", ttfb {:.0} ms", timings.http_ttfb_ms));
+    }
+    line.push_str(&format!(", {:.2}x", fragment.download_ratio));
+    line
+}
+
+fn format_download(download: &HlsDownload) -> String {
+    format!(
+        "{} in {:.2} ms ({}/s)",
+        format_bytes(download.size),
+        download.time_ms,
+        format_bytes(download.bytes_per_second)
+    )
+}
+
+fn format_timings(timings: &HlsTimings) -> String {
+    let mut parts = vec![];
+    if let Some(dns) = timings.dns_resolve_ms {
+        parts.push(format!("dns {:.0}", dns));
+    }
+    parts.push(format!("tcp {:.0}", timings.tcp_connect_ms));
+    if let Some(tls) = timings.tls_handshake_ms {
+        parts.push(format!("tls {:.0}", tls));
+    }
+    parts.push(format!("send {:.0}", timings.http_send_ms));
+    parts.push(format!("ttfb {:.0} ms", timings.http_ttfb_ms));
+    parts.join(" / ")
+}
+
+fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
@@ -0,0 +1,80 @@
+use crate::{
+    models::{
+        types::{PerformHttpResponseResultsItem, PerformHttpResponseResultsItemResult},
+        NodeInfo,
+    },
+    options::{HttpOpts, Opts},
+};
+use colorful::{Color, Colorful};
+
+use super::{
+    ping_display::{format_node_header, print_border, print_footer, sleep_if_enabled},
+    theme::Printer,
+};
+
+pub async fn display_http_response(
+    pb: &Printer,
+    config: &'static Opts,
+    opts: &HttpOpts,
+    result: &PerformHttpResponseResultsItem,
+    jobres: &PerformHttpResponseResultsItemResult,
+    node_info: &NodeInfo,
+) {
+    let width = 80;
+    print_border(pb, width);
+    pb.println(format!("│ {} {}", opts.method, result.endpoint));
+    format_node_header(pb, node_info);
+
+    let status_code = jobres.status_code as u16;
+    let unexpected = if opts.status_codes.is_empty() {
+        status_code >= 400
+    } else {
+        !opts.status_codes.contains(&status_code)
+    };
+
+    let status_line = match result.duration {
+        Some(duration) => format!("│ HTTP {} in {:.2} ms", status_code, duration),
+        None => format!("│ HTTP {}", status_code),
+    };
+    if unexpected {
+        pb.println(format!("{}", status_line.color(Color::Red)));
+    } else {
+        pb.println(status_line);
+    }
+    sleep_if_enabled(config, 250).await;
+
+    pb.println(format!("│ Body hash: {}", jobres.body_hash));
+
+    if let Some(regex) = &opts.regex {
+        if jobres.matches.is_empty() {
+            let no_match = format!("│ No matches for /{}/", regex);
+            pb.println(format!("{}", no_match.color(Color::Red)));
+        } else {
+            pb.println(format!("│ Matches for /{}/:", regex));
+            for m in &jobres.matches {
+                pb.println(format!("│   {}", m));
+            }
+        }
+    }
+    sleep_if_enabled(config, 250).await;
+
+    pb.println("│");
+    pb.println("│ --- response headers ---");
+
+    let mut headers: Vec<_> = jobres.headers.iter().collect();
+    headers.sort();
+    for (name, value) in headers {
+        pb.println(format!("│ {}: {}", name, value));
+    }
+
+    if let Some(body) = &jobres.body {
+        pb.println("│");
+        pb.println("│ --- response body ---");
+        for line in body.lines() {
+            pb.println(format!("│ {}", line));
+        }
+    }
+    sleep_if_enabled(config, 250).await;
+
+    print_footer(pb, width);
+}
@@ -0,0 +1,326 @@
+use colorful::{Color, Colorful};
+
+use crate::{
+    job::{JobData, JobResponse},
+    options::{JobKind, Opts},
+};
+
+use super::{
+    ping_display::{print_border, print_footer},
+    theme::{rtt_colour, Printer, RTT_COLOURS},
+};
+
+/// How many columns the map takes up.
+pub(super) const MAP_WIDTH: usize = 72;
+
+/// The latitudes the map covers. Nobody runs a node in Antarctica.
+const NORTH: f64 = 80.0;
+const SOUTH: f64 = -60.0;
+
+/// A rough land mask, with the longitudes of land in each 5° band of
+/// latitude from 80°N down to 60°S.
+const LAND: [&[(i16, i16)]; 28] = [
+    &[
+        (-120, -62),
+        (-73, -18),
+        (11, 27),
+        (55, 68),
+        (95, 112),
+        (135, 150),
+    ],
+    &[(-158, -141), (-125, -62), (-58, -20), (18, 31), (55, 180)],
+    &[
+        (-180, -169),
+        (-166, -85),
+        (-80, -62),
+        (-55, -22),
+        (-24, -13),
+        (12, 180),
+    ],
+    &[
+        (-180, -172),
+        (-165, -95),
+        (-78, -64),
+        (-50, -40),
+        (-22, -14),
+        (5, 180),
+    ],
+    &[
+        (-162, -150),
+        (-135, -95),
+        (-77, -60),
+        (-7, -1),
+        (5, 18),
+        (8, 12),
+        (21, 142),
+        (155, 163),
+    ],
+    &[(-130, -56), (-10, 1), (3, 143), (156, 160)],
+    &[(-125, -53), (-5, 135), (141, 144)],
+    &[(-124, -60), (-9, 47), (54, 132), (139, 146)],
+    &[(-123, -76), (-9, -1), (15, 18), (20, 122), (126, 141)],
+    &[(-120, -78), (-10, 122), (129, 135)],
+    &[(-115, -97), (-83, -80), (-15, 33), (35, 122)],
+    &[
+        (-106, -87),
+        (-85, -68),
+        (-17, 36),
+        (39, 60),
+        (68, 110),
+        (120, 122),
+    ],
+    &[
+        (-105, -88),
+        (-17, 40),
+        (42, 53),
+        (73, 85),
+        (94, 109),
+        (120, 122),
+    ],
+    &[
+        (-92, -83),
+        (-75, -61),
+        (-17, 51),
+        (74, 80),
+        (97, 109),
+        (120, 126),
+    ],
+    &[
+        (-84, -77),
+        (-78, -52),
+        (-13, 50),
+        (79, 82),
+        (98, 107),
+        (122, 126),
+    ],
+    &[
+        (-80, -50),
+        (-8, -5),
+        (8, 42),
+        (95, 106),
+        (109, 119),
+        (120, 125),
+    ],
+    &[
+        (-81, -35),
+        (9, 40),
+        (100, 106),
+        (110, 117),
+        (120, 123),
+        (132, 151),
+    ],
+    &[(-80, -35), (12, 40), (105, 115), (118, 127), (138, 150)],
+    &[(-77, -37), (13, 41), (48, 50), (125, 137), (141, 144)],
+    &[(-75, -39), (12, 40), (43, 50), (122, 146)],
+    &[(-70, -40), (13, 36), (43, 48), (113, 151)],
+    &[(-71, -48), (15, 33), (113, 153)],
+    &[(-72, -51), (17, 30), (115, 152)],
+    &[(-73, -57), (140, 150), (173, 178)],
+    &[(-74, -63), (144, 148), (168, 177)],
+    &[(-75, -66), (166, 171)],
+    &[(-74, -65), (-61, -58)],
+    &[],
+];
+
+/// The narrowest the map gets on a small terminal before it's not worth
+/// drawing.
+const MIN_MAP_WIDTH: usize = 24;
+
+/// How a node, or every node that lands in the same spot, did.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub(super) enum Marker {
+    /// Every packet came back, with this average RTT.
+    Reachable(f64),
+    /// Some packets were lost.
+    Lossy(f64),
+    /// No packets came back or the job failed.
+    Unreachable,
+}
+
+impl Marker {
+    pub fn new(avg: Option<f64>, packet_loss: Option<f64>) -> Self {
+        match (avg, packet_loss) {
+            (Some(avg), Some(loss)) if loss > 0.0 && loss < 1.0 => Marker::Lossy(avg),
+            (Some(avg), Some(loss)) if loss < 1.0 => Marker::Reachable(avg),
+            _ => Marker::Unreachable,
+        }
+    }
+
+    /// Whether this marker is worse than another, so it's the one shown when
+    /// they land in the same spot.
+    fn is_worse_than(&self, other: &Marker) -> bool {
+        match (self, other) {
+            (Marker::Reachable(a), Marker::Reachable(b)) | (Marker::Lossy(a), Marker::Lossy(b)) => {
+                a > b
+            }
+            _ => self > other,
+        }
+    }
+
+    fn symbol(&self, config: &Opts) -> String {
+        let (glyph, colour) = match self {
+            Marker::Reachable(avg) => ("●", rtt_colour(config, *avg)),
+            Marker::Lossy(avg) => ("▲", rtt_colour(config, *avg)),
+            Marker::Unreachable => ("✖", Color::Red),
+        };
+        format!("{}", glyph.color(colour).bold())
+    }
+}
+
+/// An equirectangular world map drawn in braille, with a marker for every
+/// node plotted on top.
+pub(super) struct WorldMap {
+    config: &'static Opts,
+    width: usize,
+    height: usize,
+    markers: Vec<Option<Marker>>,
+}
+
+impl WorldMap {
+    pub fn new(width: usize, config: &'static Opts) -> Self {
+        let width = width.max(MIN_MAP_WIDTH);
+        // Each character is two dots wide and four tall, and the dots are
+        // roughly square, so a row covers twice the degrees of a column.
+        let degrees_per_column = 360.0 / width as f64;
+        let height = ((NORTH - SOUTH) / (degrees_per_column * 2.0)).round() as usize;
+
+        Self {
+            config,
+            width,
+            height,
+            markers: vec![None; width * height],
+        }
+    }
+
+    pub fn plot(&mut self, lat: f64, lon: f64, marker: Marker) {
+        let column = ((lon + 180.0) / 360.0 * self.width as f64) as usize;
+        let row = ((NORTH - lat) / (NORTH - SOUTH) * self.height as f64) as usize;
+        let index = row.min(self.height - 1) * self.width + column.min(self.width - 1);
+
+        match &self.markers[index] {
+            Some(existing) if !marker.is_worse_than(existing) => {}
+            _ => self.markers[index] = Some(marker),
+        }
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let dot_lon = 180.0 / self.width as f64;
+        let dot_lat = (NORTH - SOUTH) / (self.height * 4) as f64;
+
+        (0..self.height)
+            .map(|row| {
+                (0..self.width)
+                    .map(|column| {
+                        if let Some(marker) = &self.markers[row * self.width + column] {
+                            return marker.symbol(self.config);
+                        }
+
+                        let mut dots = 0u32;
+                        for (bit, (x, y)) in BRAILLE_DOTS.iter().enumerate() {
+                            let lon = -180.0 + (column * 2 + x) as f64 * dot_lon + dot_lon / 2.0;
+                            let lat = NORTH - (row * 4 + y) as f64 * dot_lat - dot_lat / 2.0;
+                            if is_land(lat, lon) {
+                                dots |= 1 << bit;
+                            }
+                        }
+
+                        let cell = char::from_u32(0x2800 + dots).unwrap_or(' ').to_string();
+                        format!("{}", cell.color(Color::Grey50))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The colour of each RTT band, split by `--rtt-thresholds`, and what
+    /// the other markers mean.
+    pub fn legend(&self) -> String {
+        let [fast, slow, slower] = self.config.rtt_thresholds.0;
+        let labels = [
+            format!("<= {fast} ms"),
+            format!("{fast} - {slow} ms"),
+            format!("{slow} - {slower} ms"),
+            format!("> {slower} ms"),
+        ];
+
+        let mut legend: Vec<String> = RTT_COLOURS
+            .iter()
+            .zip(labels)
+            .map(|(colour, label)| format!("{} {label}", "●".color(*colour).bold()))
+            .collect();
+        legend.push("▲ packet loss".to_string());
+        legend.push(format!("{} unreachable", "✖".color(Color::Red).bold()));
+        legend.join("   ")
+    }
+}
+
+/// The (x, y) position of each bit of a braille character.
+const BRAILLE_DOTS: [(usize, usize); 8] = [
+    (0, 0),
+    (0, 1),
+    (0, 2),
+    (1, 0),
+    (1, 1),
+    (1, 2),
+    (0, 3),
+    (1, 3),
+];
+
+/// The land mask as (north, west, east) spans of each 5° band, so it can be
+/// drawn outside the terminal too.
+pub fn land_spans() -> impl Iterator<Item = (f64, f64, f64)> {
+    LAND.iter().enumerate().flat_map(|(band, spans)| {
+        let north = NORTH - band as f64 * 5.0;
+        spans
+            .iter()
+            .map(move |(west, east)| (north, *west as f64, *east as f64))
+    })
+}
+
+fn is_land(lat: f64, lon: f64) -> bool {
+    let band = ((NORTH - lat) / 5.0) as usize;
+    LAND.get(band).is_some_and(|spans| {
+        spans
+            .iter()
+            .any(|(west, east)| (*west as f64..=*east as f64).contains(&lon))
+    })
+}
+
+/// Prints a map of every node that pinged each endpoint, coloured by how it
+/// did.
+pub fn print_map(pb: &Printer, config: &'static Opts, responses: &[JobResponse]) {
+    let JobKind::Icmp(ref opts) = config.job else {
+        return;
+    };
+
+    for endpoint in &opts.endpoints {
+        let width = pb.fit(MAP_WIDTH + 8);
+        let mut map = WorldMap::new(width.saturating_sub(8), config);
+
+        for response in responses {
+            let JobData::Icmp { response: icmp, .. } = &response.data else {
+                continue;
+            };
+            let Some(node_info) = &icmp.node_info else {
+                continue;
+            };
+
+            for item in icmp.results.iter().filter(|i| i.endpoint == *endpoint) {
+                let result = item.result.as_ref().filter(|_| item.error.is_none());
+                let marker = Marker::new(result.map(|r| r.avg), result.map(|r| r.packet_loss));
+                map.plot(node_info.lat, node_info.lon, marker);
+            }
+        }
+
+        print_border(pb, width);
+        pb.println(format!("│ MAP {endpoint}"));
+        pb.println("│ ---");
+        for line in map.lines() {
+            pb.println(format!("│ {line}"));
+        }
+        pb.println("│ ---");
+        pb.println(format!("│ {}", map.legend()));
+        print_footer(pb, width);
+        pb.println("");
+    }
+}
@@ -1,5 +1,19 @@
+mod compact;
+mod dns_display;
+mod histogram;
+mod hls_display;
+mod http_display;
+mod map;
+pub use map::land_spans;
+mod order;
 mod ping_display;
 mod print;
 pub use print::*;
 mod progress;
 pub use progress::*;
+mod summary;
+mod sweep;
+mod theme;
+pub use theme::{truncate, wants_colour, Printer};
+mod tui;
+mod watch;
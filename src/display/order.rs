@@ -0,0 +1,228 @@
+use std::cmp::Ordering;
+
+use colorful::Colorful;
+use console::measure_text_width;
+
+use crate::{
+    job::{JobData, JobResponse},
+    models::NodeInfo,
+    options::{FilterField, Opts, ResultGrouping, ResultOrder},
+};
+
+use super::{print::display_job, theme::Printer};
+
+/// What a node's response boils down to for sorting, grouping and
+/// filtering. With several endpoints, the worst of them counts.
+struct NodeFacts<'a> {
+    response: &'a JobResponse,
+    node_info: Option<NodeInfo>,
+    rtt: Option<f64>,
+    min: Option<f64>,
+    max: Option<f64>,
+    /// Packet loss from 0 to 1. Errors count as losing everything.
+    loss: Option<f64>,
+    failed: bool,
+}
+
+impl<'a> NodeFacts<'a> {
+    fn new(response: &'a JobResponse) -> Self {
+        let mut facts = Self {
+            response,
+            node_info: None,
+            rtt: None,
+            min: None,
+            max: None,
+            loss: None,
+            failed: false,
+        };
+
+        match &response.data {
+            JobData::Icmp { response: icmp, .. } => {
+                facts.node_info = icmp.node_info.clone();
+                for item in &icmp.results {
+                    match item.result.as_ref().filter(|_| item.error.is_none()) {
+                        Some(result) => {
+                            facts.loss = Some(facts.loss.unwrap_or(0.0).max(result.packet_loss));
+                            if result.packet_loss < 1.0 {
+                                facts.rtt = Some(facts.rtt.unwrap_or(0.0).max(result.avg));
+                                facts.min = Some(facts.min.unwrap_or(f64::MAX).min(result.min));
+                                facts.max = Some(facts.max.unwrap_or(0.0).max(result.max));
+                            } else {
+                                facts.failed = true;
+                            }
+                        }
+                        None => {
+                            facts.loss = Some(1.0);
+                            facts.failed = true;
+                        }
+                    }
+                }
+            }
+            JobData::Dns(dns) => {
+                facts.node_info = dns.node_info.clone().map(Into::into);
+                facts.record_durations(dns.results.iter().map(|r| (r.duration, &r.error)));
+            }
+            JobData::Http(http) => {
+                facts.node_info = http.node_info.clone().map(Into::into);
+                facts.record_durations(http.results.iter().map(|r| (r.duration, &r.error)));
+            }
+            JobData::Hls(hls) => {
+                facts.node_info = hls.node_info.clone().map(Into::into);
+                facts.record_durations(hls.results.iter().map(|r| (r.duration, &r.error)));
+            }
+        }
+
+        facts
+    }
+
+    /// Other probes have no packets to lose, so their duration stands in for
+    /// the RTT and any error counts as a failure.
+    fn record_durations<'b>(
+        &mut self,
+        results: impl Iterator<Item = (Option<f64>, &'b Option<String>)>,
+    ) {
+        for (duration, error) in results {
+            if error.is_some() {
+                self.failed = true;
+                self.loss = Some(1.0);
+                continue;
+            }
+            if let Some(duration) = duration {
+                self.rtt = Some(self.rtt.unwrap_or(0.0).max(duration));
+                self.min = Some(self.min.unwrap_or(f64::MAX).min(duration));
+                self.max = Some(self.max.unwrap_or(0.0).max(duration));
+            }
+            self.loss.get_or_insert(0.0);
+        }
+    }
+
+    fn country(&self) -> String {
+        match &self.node_info {
+            Some(node) => node.country().map_or(node.country_code.clone(), |c| {
+                c.iso_short_name().to_string()
+            }),
+            None => "Unknown".to_string(),
+        }
+    }
+
+    fn text(&self, field: FilterField) -> String {
+        let node = self.node_info.as_ref();
+        match field {
+            FilterField::Country => self.country(),
+            FilterField::Continent => node.map_or("Unknown", |n| n.continent_name()).to_string(),
+            FilterField::City => node.map(|n| n.city.clone()).unwrap_or_default(),
+            FilterField::Isp => node.map(|n| n.isp.clone()).unwrap_or_default(),
+            FilterField::Network => node.map_or("", |n| n.network_type()).to_string(),
+            FilterField::Region => self.response.region.name(),
+            _ => String::new(),
+        }
+    }
+
+    fn number(&self, field: FilterField) -> Option<f64> {
+        match field {
+            FilterField::Loss => self.loss.map(|loss| loss * 100.0),
+            FilterField::Rtt => self.rtt,
+            FilterField::Min => self.min,
+            FilterField::Max => self.max,
+            _ => None,
+        }
+    }
+
+    fn group(&self, grouping: ResultGrouping) -> String {
+        let Some(node) = &self.node_info else {
+            return "Unknown".to_string();
+        };
+
+        match grouping {
+            ResultGrouping::Continent => node.continent_name().to_string(),
+            ResultGrouping::Country => self.country(),
+            ResultGrouping::Isp => node.isp.clone(),
+            ResultGrouping::NetworkType => node.network_type().to_string(),
+        }
+    }
+}
+
+/// Displays the responses held back for `--sort`, `--group-by`, `--filter`
+/// and `--only-failures`, once every job has finished.
+pub async fn display_ordered(pb: &Printer, config: &'static Opts, responses: &[JobResponse]) {
+    let mut nodes: Vec<NodeFacts> = responses
+        .iter()
+        .map(NodeFacts::new)
+        .filter(|node| !config.only_failures || node.failed)
+        .filter(|node| {
+            config.filters.iter().all(|filter| {
+                if filter.field.is_numeric() {
+                    node.number(filter.field)
+                        .is_some_and(|n| filter.matches_number(n))
+                } else {
+                    filter.matches_text(&node.text(filter.field))
+                }
+            })
+        })
+        .collect();
+
+    if nodes.is_empty() {
+        pb.println(format!(
+            "No results match the filter, {} hidden",
+            responses.len()
+        ));
+        pb.println("");
+        return;
+    }
+
+    if let Some(order) = config.sort {
+        nodes.sort_by(|a, b| compare(order, a, b));
+    }
+
+    let Some(grouping) = config.group_by else {
+        for node in &nodes {
+            display_job(pb, config, node.response).await;
+        }
+        return;
+    };
+
+    let mut groups: Vec<(String, Vec<&NodeFacts>)> = vec![];
+    for node in &nodes {
+        let name = node.group(grouping);
+        match groups.iter_mut().find(|(group, _)| *group == name) {
+            Some((_, members)) => members.push(node),
+            None => groups.push((name, vec![node])),
+        }
+    }
+    groups.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    for (name, members) in groups {
+        let heading = format!("━━ {name} · {} nodes ", members.len());
+        let fill = pb.fit(80).saturating_sub(measure_text_width(&heading));
+        pb.println(format!(
+            "{}",
+            format!("{heading}{}", "━".repeat(fill)).bold()
+        ));
+        pb.println("");
+        for node in members {
+            display_job(pb, config, node.response).await;
+        }
+    }
+}
+
+/// Orders nodes from best to worst, so the worst end up right above the
+/// summary. Nodes without a value go last.
+fn compare(order: ResultOrder, a: &NodeFacts, b: &NodeFacts) -> Ordering {
+    match order {
+        ResultOrder::Rtt => cmp_missing_last(a.rtt, b.rtt),
+        ResultOrder::Loss => cmp_missing_last(a.loss, b.loss).then(cmp_missing_last(a.rtt, b.rtt)),
+        ResultOrder::Country => a.country().cmp(&b.country()),
+        ResultOrder::Isp => a.text(FilterField::Isp).cmp(&b.text(FilterField::Isp)),
+    }
+}
+
+/// Compares two values in ascending order, with missing values after the
+/// rest.
+pub(super) fn cmp_missing_last(a: Option<f64>, b: Option<f64>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
@@ -1,76 +1,117 @@
 use crate::{
-    models::types::{
-        PerformIcmpResponseNodeInfo, PerformIcmpResponseResultsItem,
-        PerformIcmpResponseResultsItemResult,
+    models::{
+        types::{
+            PerformIcmpResponseNodeInfo, PerformIcmpResponseResultsItem,
+            PerformIcmpResponseResultsItemResult,
+        },
+        NodeInfo,
     },
-    options::Opts,
+    options::{JobKind, NodeIdentity, Opts},
 };
 use colorful::{Color, Colorful};
-use indicatif::ProgressBar;
-use std::*;
-use sync::Arc;
 use tracing::warn;
 
-fn print_border(pb: &ProgressBar, width: usize) {
+use super::theme::{loss_colour, result_colour, rtt_colour, Printer};
+
+/// Size of the ICMP echo header that precedes the payload in every reply.
+const ICMP_HEADER_SIZE: usize = 8;
+
+/// Boxes are drawn `width` columns wide, or as wide as the terminal if it's
+/// narrower.
+pub(super) fn print_border(pb: &Printer, width: usize) {
+    let width = pb.fit(width).max(2);
     pb.println("┌".to_string() + &"─".repeat(width - 2) + "┐");
 }
 
-fn print_footer(pb: &ProgressBar, width: usize) {
+pub(super) fn print_footer(pb: &Printer, width: usize) {
+    let width = pb.fit(width).max(2);
     pb.println("└".to_string() + &"─".repeat(width - 2) + "┘");
 }
 
-async fn sleep_if_enabled(config: &'static Opts, duration: u64) {
-    if !config.no_delay {
+/// Paces the display of a result as it lands. Results held back until the
+/// end are replayed all at once, as there's nothing left to wait for.
+pub(super) async fn sleep_if_enabled(config: &'static Opts, duration: u64) {
+    if !config.no_delay && !config.buffers_results() {
         tokio::time::sleep(std::time::Duration::from_millis(duration)).await;
     }
 }
 
+/// Whether to print a line per packet, ping style. The API only reports
+/// aggregates, so those lines are an approximation and are opt-in.
+fn emulate_ping(config: &Opts) -> bool {
+    matches!(config.job, JobKind::Icmp(ref opts) if opts.emulate_ping)
+}
+
 pub async fn display_success_ping(
-    pb: &ProgressBar,
+    pb: &Printer,
     config: &'static Opts,
     endpoint: &str,
+    payload_size: usize,
     jobres: &PerformIcmpResponseResultsItemResult,
     node_info: &PerformIcmpResponseNodeInfo,
 ) {
     let width = 80;
     print_border(pb, width);
-    format_ping_header(pb, config, endpoint, &jobres.ip_address, node_info);
+    format_ping_header(pb, endpoint, &jobres.ip_address, payload_size, node_info);
+    print_success_statistics(pb, config, endpoint, payload_size, jobres).await;
+    print_footer(pb, width);
+}
 
-    let trips = jobres.trips as usize;
-    for i in 0..trips {
-        let time = jobres.min + (jobres.max - jobres.min) * (i as f64 / (trips - 1) as f64);
-        pb.println(format!(
-            "│ 64 bytes from {}: icmp_seq={} ttl=120 time={:.2} ms",
-            jobres.ip_address, i, time
-        ));
-        sleep_if_enabled(config, time as u64).await;
+async fn print_success_statistics(
+    pb: &Printer,
+    config: &'static Opts,
+    endpoint: &str,
+    payload_size: usize,
+    jobres: &PerformIcmpResponseResultsItemResult,
+) {
+    if emulate_ping(config) {
+        let trips = jobres.trips as usize;
+        for i in 0..trips {
+            let time = jobres.min
+                + (jobres.max - jobres.min) * (i as f64 / trips.saturating_sub(1).max(1) as f64);
+            pb.println(format!(
+                "│ {} bytes from {}: icmp_seq={} time~{:.2} ms",
+                payload_size + ICMP_HEADER_SIZE,
+                jobres.ip_address,
+                i,
+                time
+            ));
+            sleep_if_enabled(config, time as u64).await;
+        }
+        pb.println("│");
     }
 
-    pb.println("│");
     pb.println(format!("│ --- {endpoint} ping statistics ---"));
 
     sleep_if_enabled(config, 250).await;
 
-    pb.println(format!(
+    let packets_line = format!(
         "│ {} packets transmitted, {} packets received, {:.1}% packet loss",
         jobres.packets_sent,
         jobres.packets_recv,
         jobres.packet_loss * 100.0
-    ));
+    );
+    if jobres.packet_loss > 0.0 {
+        let colour = loss_colour(config, jobres.packet_loss);
+        pb.println(format!("{}", packets_line.color(colour)));
+    } else {
+        pb.println(packets_line);
+    }
     sleep_if_enabled(config, 250).await;
 
-    pb.println(format!(
+    let round_trip_line = format!(
         "│ round-trip min/avg/max/stddev = {:.3}/{:.3}/{:.3}/{:.3} ms",
         jobres.min, jobres.avg, jobres.max, jobres.std_dev
-    ));
+    );
+    let colour = rtt_colour(config, jobres.avg);
+    pb.println(format!("{}", round_trip_line.color(colour)));
     sleep_if_enabled(config, 250).await;
-
-    print_footer(pb, width);
 }
 
 pub async fn display_failed_ping(
-    pb: &ProgressBar,
+    pb: &Printer,
     config: &'static Opts,
+    payload_size: usize,
     jobres: &PerformIcmpResponseResultsItem,
     node_info: &PerformIcmpResponseNodeInfo,
 ) {
@@ -80,12 +121,22 @@ pub async fn display_failed_ping(
         .result
         .as_ref()
         .map_or("Unknown".to_string(), |r| r.ip_address.clone());
-    format_ping_header(pb, config, &jobres.endpoint, &ip_address, node_info);
+    format_ping_header(pb, &jobres.endpoint, &ip_address, payload_size, node_info);
+    print_failed_statistics(pb, config, jobres).await;
+    print_footer(pb, width);
+}
 
+async fn print_failed_statistics(
+    pb: &Printer,
+    config: &'static Opts,
+    jobres: &PerformIcmpResponseResultsItem,
+) {
     let attempts = jobres.result.as_ref().map_or(4, |r| r.attempts as usize);
-    for index in 0..attempts {
-        pb.println(format!("│ Request timeout for icmp_seq {}", index));
-        sleep_if_enabled(config, 500).await;
+    if emulate_ping(config) {
+        for index in 0..attempts {
+            pb.println(format!("│ Request timeout for icmp_seq {}", index));
+            sleep_if_enabled(config, 500).await;
+        }
     }
 
     pb.println(format!("│ --- {} ping statistics ---", jobres.endpoint));
@@ -108,35 +159,119 @@ pub async fn display_failed_ping(
 
     pb.println(format!("{}", error_string.color(Color::Red)));
     sleep_if_enabled(config, 250).await;
+}
+
+/// Displays every endpoint pinged by a single node in one box, so targets
+/// measured from the same vantage point can be compared side by side.
+pub async fn display_grouped_pings(
+    pb: &Printer,
+    config: &'static Opts,
+    payload_size: usize,
+    results: &[PerformIcmpResponseResultsItem],
+    node_info: &PerformIcmpResponseNodeInfo,
+) {
+    let width = 80;
+    print_border(pb, width);
+    pb.println(format!(
+        "│ PING {} endpoints from the same node",
+        results.len()
+    ));
+    format_node_header(pb, node_info);
+
+    for result in results {
+        let ip_address = result
+            .result
+            .as_ref()
+            .map_or("Unknown".to_string(), |r| r.ip_address.clone());
+        pb.println(format!(
+            "│ PING {} ({}): {} data bytes",
+            result.endpoint, ip_address, payload_size
+        ));
+
+        match (&result.error, &result.result) {
+            (Some(err), _) => {
+                let error_line = format!("│ {}", err);
+                pb.println(format!("{}", error_line.color(Color::Red)));
+            }
+            (None, Some(job_result)) if job_result.packet_loss < 1.0 => {
+                print_success_statistics(pb, config, &result.endpoint, payload_size, job_result)
+                    .await;
+            }
+            _ => print_failed_statistics(pb, config, result).await,
+        }
+        pb.println("│");
+    }
+
+    pb.println("│ --- endpoint comparison ---");
+    let endpoint_width = results
+        .iter()
+        .map(|r| r.endpoint.chars().count())
+        .max()
+        .unwrap_or(0);
+    for result in results {
+        let line = match &result.result {
+            Some(r) if r.packet_loss >= 1.0 => {
+                format!("│ {:<endpoint_width$}  100.0% loss", result.endpoint)
+            }
+            Some(r) => format!(
+                "│ {:<endpoint_width$}  {:>5.1}% loss  avg {:>8.3} ms  stddev {:>7.3} ms",
+                result.endpoint,
+                r.packet_loss * 100.0,
+                r.avg,
+                r.std_dev
+            ),
+            None => format!("│ {:<endpoint_width$}  no result", result.endpoint),
+        };
+        let colour = match (&result.error, &result.result) {
+            (None, Some(r)) if r.packet_loss < 1.0 => {
+                result_colour(config, Some(r.avg), r.packet_loss)
+            }
+            _ => Color::Red,
+        };
+        pb.println(format!("{}", line.color(colour)));
+    }
+    sleep_if_enabled(config, 250).await;
 
     print_footer(pb, width);
 }
 
 pub fn format_ping_header(
-    pb: &ProgressBar,
-    config: &Opts,
+    pb: &Printer,
     endpoint: &str,
     ip_address: &str,
+    payload_size: usize,
     node_info: &PerformIcmpResponseNodeInfo,
 ) {
     // PING line
-    let ping_line = format!("│ PING {} ({}): 56 data bytes", endpoint, ip_address);
+    let ping_line = format!(
+        "│ PING {} ({}): {} data bytes",
+        endpoint, ip_address, payload_size
+    );
     pb.println(ping_line);
 
-    let Some(alpha2) = keshvar::Alpha2::try_from(node_info.country_code.as_str()).ok() else {
+    format_node_header(pb, node_info);
+}
+
+/// The `--node` argument that sends jobs back to the node, quoted for a shell.
+pub(super) fn node_argument(node_info: &NodeInfo) -> String {
+    let identity = NodeIdentity::from(node_info).to_string();
+    format!("--node '{}'", identity.replace('\'', r"'\''"))
+}
+
+/// Prints the origin, ISP, system and node lines describing the node that
+/// served a job.
+pub(super) fn format_node_header(pb: &Printer, node_info: &NodeInfo) {
+    let Some(country) = node_info.country() else {
         warn!("Failed to get alpha2 for country code");
         return;
     };
 
     // Origin line
-    let country_emoji = alpha2.to_country().emoji();
+    let country_emoji = country.emoji();
 
-    let country_name = alpha2.to_country().iso_short_name();
+    let country_name = country.iso_short_name();
 
-    let coordinates = match (node_info.lat, node_info.lon) {
-        (lat, lon) => format!("({:.2}°N, {:.2}°E)", lat, lon),
-        _ => String::new(),
-    };
+    let coordinates = format!("({:.2}°N, {:.2}°E)", node_info.lat, node_info.lon);
 
     let origin_line = format!(
         "│ ├── Origin: {} {}, {} {}",
@@ -149,9 +284,13 @@ pub fn format_ping_header(
     pb.println(isp_line);
 
     // System line
-    let system_line = format!("│ └── System: {}", node_info.operating_system);
+    let system_line = format!("│ ├── System: {}", node_info.operating_system);
     pb.println(system_line);
 
+    // Node line, to pin later jobs to the same node
+    let node_line = format!("│ └── Node: {}", node_argument(node_info));
+    pb.println(node_line);
+
     // Separator line
     pb.println("│ ---");
 }
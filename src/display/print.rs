@@ -1,13 +1,50 @@
-use std::sync::Arc;
-
-use indicatif::ProgressBar;
 use tracing::{error, warn};
 
-use crate::{models::types::PerformIcmpResponse, options::Opts};
+use crate::{
+    job::{JobData, JobResponse},
+    models::types::{
+        PerformDnsResponse, PerformHlsResponse, PerformHttpResponse, PerformIcmpResponse,
+    },
+    options::{JobKind, Opts},
+};
 
-use super::ping_display;
+use super::{compact, dns_display, hls_display, http_display, ping_display, theme::Printer};
+
+pub async fn display_job(pb: &Printer, config: &'static Opts, job_data: &JobResponse) {
+    match &job_data.data {
+        JobData::Icmp {
+            payload_size,
+            response,
+        } => display_icmp_job(pb, config, *payload_size, response).await,
+        JobData::Dns(response) => display_dns_job(pb, config, response).await,
+        JobData::Http(response) => display_http_job(pb, config, response).await,
+        JobData::Hls(response) => display_hls_job(pb, config, response).await,
+    }
+}
+
+async fn display_icmp_job(
+    pb: &Printer,
+    config: &'static Opts,
+    payload_size: usize,
+    job_data: &PerformIcmpResponse,
+) {
+    if config.compact {
+        compact::print_compact_lines(pb, config, payload_size, job_data);
+        return;
+    }
+
+    if job_data.results.len() > 1 {
+        let Some(ref node_info) = job_data.node_info else {
+            warn!("Unable to get node info for job");
+            return;
+        };
+
+        ping_display::display_grouped_pings(pb, config, payload_size, &job_data.results, node_info)
+            .await;
+        pb.println("");
+        return;
+    }
 
-pub async fn display_job(pb: &ProgressBar, config: &'static Opts, job_data: PerformIcmpResponse) {
     for result in &job_data.results {
         if let Some(err) = &result.error {
             error!(?err, "Fatal job error.");
@@ -21,14 +58,16 @@ pub async fn display_job(pb: &ProgressBar, config: &'static Opts, job_data: Perf
             };
 
             if job_result.packet_loss == 1.0 {
-                ping_display::display_failed_ping(&pb, &config, result, node_info).await;
+                ping_display::display_failed_ping(pb, config, payload_size, result, node_info)
+                    .await;
                 continue;
             }
 
             ping_display::display_success_ping(
-                &pb,
-                &config,
+                pb,
+                config,
                 &result.endpoint,
+                payload_size,
                 job_result,
                 node_info,
             )
@@ -38,3 +77,81 @@ pub async fn display_job(pb: &ProgressBar, config: &'static Opts, job_data: Perf
         pb.println("");
     }
 }
+
+async fn display_dns_job(pb: &Printer, config: &'static Opts, job_data: &PerformDnsResponse) {
+    let node_info = job_data.node_info.clone().map(Into::into);
+
+    for result in &job_data.results {
+        if let Some(err) = &result.error {
+            error!(?err, "Fatal job error.");
+            continue;
+        }
+
+        if let Some(job_result) = &result.result {
+            let Some(ref node_info) = node_info else {
+                warn!("Unable to get node info for job");
+                continue;
+            };
+
+            dns_display::display_dns_lookup(pb, config, result, job_result, node_info).await;
+        }
+
+        pb.println("");
+    }
+}
+
+async fn display_http_job(pb: &Printer, config: &'static Opts, job_data: &PerformHttpResponse) {
+    let JobKind::Http(ref opts) = config.job else {
+        return;
+    };
+    let node_info = job_data.node_info.clone().map(Into::into);
+
+    for result in &job_data.results {
+        if let Some(err) = &result.error {
+            error!(?err, "Fatal job error.");
+            continue;
+        }
+
+        if let Some(job_result) = &result.result {
+            let Some(ref node_info) = node_info else {
+                warn!("Unable to get node info for job");
+                continue;
+            };
+
+            http_display::display_http_response(pb, config, opts, result, job_result, node_info)
+                .await;
+        }
+
+        pb.println("");
+    }
+}
+
+async fn display_hls_job(pb: &Printer, config: &'static Opts, job_data: &PerformHlsResponse) {
+    let node_info = job_data.node_info.clone().map(Into::into);
+
+    for result in &job_data.results {
+        if let Some(err) = &result.error {
+            error!(?err, "Fatal job error.");
+            continue;
+        }
+
+        if let Some(job_result) = result.result.clone() {
+            let Some(ref node_info) = node_info else {
+                warn!("Unable to get node info for job");
+                continue;
+            };
+
+            hls_display::display_hls_stream(
+                pb,
+                config,
+                &result.endpoint,
+                result.duration,
+                &job_result.into(),
+                node_info,
+            )
+            .await;
+        }
+
+        pb.println("");
+    }
+}
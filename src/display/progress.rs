@@ -1,27 +1,47 @@
-use color_eyre::eyre::Result;
+use chrono::Utc;
+use color_eyre::eyre::{self, Result};
 // progress.rs
 use indicatif::{ProgressBar, ProgressStyle};
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::mpsc::{self, Receiver, Sender};
-use tokio::task::JoinHandle;
+use std::{io::IsTerminal, time::Duration};
+use tokio::sync::mpsc::{self, Receiver, Sender, UnboundedReceiver, UnboundedSender};
 
 use crate::display::display_job;
-use crate::models::types::PerformIcmpResponse;
-use crate::options::Opts;
+use crate::history;
+use crate::job::JobResponse;
+use crate::options::{EarthRegion, JobKind, Opts, OutputFormat};
+use crate::output::{self, JobFailure, RunRecord};
+
+use super::{
+    histogram::print_histograms, map::print_map, order::display_ordered, summary::print_summary,
+    sweep::SweepReport, tui, wants_colour, watch::WatchTable, Printer,
+};
+
+/// What the scheduler hands the display as jobs finish.
+pub(super) enum Update {
+    Job(JobResponse),
+    Failed {
+        region: EarthRegion,
+        error: String,
+    },
+    /// Every job of a `--watch` cycle has finished.
+    CycleFinished,
+}
 
 pub struct ProgressDisplay {
-    bar: ProgressBar,
+    printer: Printer,
     config: &'static Opts,
+    sweep: Option<SweepReport>,
+    watch: Option<WatchTable>,
+    run: RunRecord,
 
-    rx: Receiver<PerformIcmpResponse>,
+    rx: Receiver<Update>,
+    reruns: Option<UnboundedSender<EarthRegion>>,
 }
 
-#[derive(Clone)]
 pub struct ProgressUpdater {
     bar: ProgressBar,
-    tx: Sender<PerformIcmpResponse>,
+    tx: Sender<Update>,
+    reruns: Option<UnboundedReceiver<EarthRegion>>,
 }
 
 impl Drop for ProgressUpdater {
@@ -31,46 +51,172 @@ impl Drop for ProgressUpdater {
 }
 
 impl ProgressUpdater {
-    pub(crate) async fn display_job(
-        &self,
-        job: progenitor::progenitor_client::ResponseValue<
-            crate::models::types::PerformIcmpResponse,
-        >,
-    ) {
-        let _ = self.tx.send(job.into_inner()).await;
+    pub(crate) async fn display_job(&self, job: JobResponse) {
+        let _ = self.tx.send(Update::Job(job)).await;
+        self.bar.inc(1);
+    }
+
+    pub(crate) async fn job_failed(&self, region: &EarthRegion, error: String) {
+        let _ = self
+            .tx
+            .send(Update::Failed {
+                region: region.clone(),
+                error,
+            })
+            .await;
         self.bar.inc(1);
     }
+
+    pub(crate) async fn cycle_finished(&self) {
+        let _ = self.tx.send(Update::CycleFinished).await;
+    }
+
+    /// Whether the display has stopped listening, so there's no point
+    /// sending any more jobs.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+
+    /// The regions the dashboard asks to run again, when --tui is used.
+    pub(crate) fn take_reruns(&mut self) -> Option<UnboundedReceiver<EarthRegion>> {
+        self.reruns.take()
+    }
 }
 
 impl ProgressDisplay {
     pub fn new(config: &'static Opts) -> Result<(Self, ProgressUpdater)> {
+        if config.tui && !std::io::stdout().is_terminal() {
+            eyre::bail!("--tui needs an interactive terminal");
+        }
+
         let mut spinner_style = ProgressStyle::default_spinner()
             .tick_chars("-\\|/")
             .template("{spinner:.green} {msg:.cyan/blue} [{elapsed_precise}] {pos}/{len}")?;
 
-        let bar = ProgressBar::new((config.attempts * config.regions.len()) as u64);
+        let bar = match config.job_count() {
+            _ if config.output != OutputFormat::Text || config.tui => ProgressBar::hidden(),
+            Some(count) => ProgressBar::new(count as u64),
+            None => ProgressBar::no_length(),
+        };
 
-        let world_ticker = format!("{}", console::Emoji("🌍🌍🌎🌎🌏🌏🌎🌎🌍🌍", "-\\|/"));
-        spinner_style = spinner_style.tick_chars(&world_ticker);
+        if !wants_colour(config.plain) {
+            console::set_colors_enabled_stderr(false);
+        }
+        if !config.plain {
+            let world_ticker = format!("{}", console::Emoji("🌍🌍🌎🌎🌏🌏🌎🌎🌍🌍", "-\\|/"));
+            spinner_style = spinner_style.tick_chars(&world_ticker);
+        }
         bar.enable_steady_tick(Duration::from_millis(350));
 
         bar.set_style(spinner_style);
 
         let (tx, rx) = mpsc::channel(config.concurrency);
+        let (rerun_tx, rerun_rx) = if config.tui {
+            let (rerun_tx, rerun_rx) = mpsc::unbounded_channel();
+            (Some(rerun_tx), Some(rerun_rx))
+        } else {
+            (None, None)
+        };
+
+        let sweep = match &config.job {
+            JobKind::Icmp(opts) if opts.sweep.is_some() => Some(SweepReport::new(opts.timeout)),
+            _ => None,
+        };
+        let watch = config.watch().map(|_| WatchTable::default());
 
         Ok((
             Self {
-                bar: bar.clone(),
+                printer: Printer::new(bar.clone(), config),
                 config,
+                sweep,
+                watch,
+                run: RunRecord {
+                    started_at: Utc::now(),
+                    finished_at: Utc::now(),
+                    responses: vec![],
+                    failures: vec![],
+                },
                 rx,
+                reruns: rerun_tx,
+            },
+            ProgressUpdater {
+                bar,
+                tx,
+                reruns: rerun_rx,
             },
-            ProgressUpdater { bar, tx },
         ))
     }
 
-    pub async fn display_job_thread(&mut self) {
-        while let Some(x) = self.rx.recv().await {
-            display_job(&self.bar, self.config, x).await;
+    pub async fn display_job_thread(&mut self) -> Result<()> {
+        if let Some(reruns) = self.reruns.take() {
+            self.run.responses = tui::run(self.config, &mut self.rx, reruns).await?;
+        }
+
+        while let Some(update) = self.rx.recv().await {
+            let x = match update {
+                Update::Job(x) => x,
+                Update::CycleFinished => {
+                    if let (Some(watch), OutputFormat::Text) =
+                        (&mut self.watch, &self.config.output)
+                    {
+                        watch.finish_cycle();
+                        watch.draw(&self.printer, self.config);
+                    }
+                    continue;
+                }
+                Update::Failed { region, error } => {
+                    let failure = JobFailure {
+                        region,
+                        received_at: Utc::now(),
+                        error,
+                    };
+                    output::write_failure(self.config, &failure)?;
+                    self.run.failures.push(failure);
+                    continue;
+                }
+            };
+            output::write_response(self.config, &x)?;
+
+            if let Some(watch) = &mut self.watch {
+                if self.config.output == OutputFormat::Text {
+                    watch.record(&x);
+                    // Piped output gets one table per cycle instead.
+                    if self.printer.is_terminal() {
+                        watch.draw(&self.printer, self.config);
+                    }
+                }
+                continue;
+            }
+
+            if let Some(sweep) = &mut self.sweep {
+                sweep.record(&x);
+            }
+            if self.config.output == OutputFormat::Text && !self.config.buffers_results() {
+                display_job(&self.printer, self.config, &x).await;
+            }
+            self.run.responses.push(x);
+        }
+        self.run.finished_at = Utc::now();
+
+        if self.config.buffers_results() {
+            display_ordered(&self.printer, self.config, &self.run.responses).await;
         }
+
+        if let Some(sweep) = &self.sweep {
+            sweep.print(&self.printer);
+        } else if self.watch.is_none() && !self.config.tui {
+            print_summary(&self.printer, self.config, &self.run.responses);
+            // With every job failed, there's nothing to chart.
+            if self.config.histogram && !self.run.responses.is_empty() {
+                print_histograms(&self.printer, self.config, &self.run.responses);
+            }
+            if self.config.map && !self.run.responses.is_empty() {
+                print_map(&self.printer, self.config, &self.run.responses);
+            }
+        }
+
+        output::write_run(self.config, &self.run)?;
+        history::record_run(self.config, &self.run);
+        output::push_run(self.config, &self.run).await
     }
 }
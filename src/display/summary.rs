@@ -0,0 +1,240 @@
+use colorful::{Color, Colorful};
+
+use crate::{
+    job::{JobData, JobResponse},
+    models::{types::PerformIcmpResponseResultsItem, NodeInfo},
+    options::{EarthRegion, JobKind, Opts, SummaryGrouping},
+    stats::GroupSummary,
+};
+
+use super::{
+    ping_display::{print_border, print_footer},
+    theme::{truncate, Printer},
+};
+
+const NAME_WIDTH: usize = 24;
+const MIN_NAME_WIDTH: usize = 12;
+
+/// A column of the summary table after the group name.
+#[derive(Clone, Copy, PartialEq)]
+enum Column {
+    Nodes,
+    Success,
+    Min,
+    P50,
+    P95,
+    Max,
+    Jitter,
+    Loss,
+}
+
+const COLUMNS: [Column; 8] = [
+    Column::Nodes,
+    Column::Success,
+    Column::Min,
+    Column::P50,
+    Column::P95,
+    Column::Max,
+    Column::Jitter,
+    Column::Loss,
+];
+
+/// The columns given up, in order, when the terminal is too narrow for all
+/// of them.
+const DROP_ORDER: [Column; 5] = [
+    Column::Jitter,
+    Column::P95,
+    Column::Min,
+    Column::Max,
+    Column::Success,
+];
+
+impl Column {
+    fn header(self) -> &'static str {
+        match self {
+            Column::Nodes => "nodes",
+            Column::Success => "success",
+            Column::Min => "min",
+            Column::P50 => "p50",
+            Column::P95 => "p95",
+            Column::Max => "max",
+            Column::Jitter => "jitter",
+            Column::Loss => "loss",
+        }
+    }
+
+    fn width(self) -> usize {
+        match self {
+            Column::Nodes => 5,
+            Column::Success => 7,
+            Column::Min | Column::P50 | Column::P95 | Column::Max => 9,
+            Column::Jitter => 8,
+            Column::Loss => 6,
+        }
+    }
+
+    fn value(self, summary: &GroupSummary) -> String {
+        let ms = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{v:.1} ms"));
+
+        match self {
+            Column::Nodes => summary.nodes.to_string(),
+            Column::Success => format!("{:.1}%", summary.success_rate() * 100.0),
+            Column::Min => ms(summary.rtt_min),
+            Column::P50 => ms(summary.rtt_p50),
+            Column::P95 => ms(summary.rtt_p95),
+            Column::Max => ms(summary.rtt_max),
+            Column::Jitter => ms(summary.jitter),
+            Column::Loss => summary
+                .packet_loss
+                .map_or("-".to_string(), |l| format!("{:.1}%", l * 100.0)),
+        }
+    }
+}
+
+/// Which columns fit on the terminal, and how wide the group names can be.
+struct Layout {
+    name_width: usize,
+    columns: Vec<Column>,
+}
+
+impl Layout {
+    /// Shrinks the names first, then drops the columns that matter least
+    /// until the table fits within `available` columns.
+    fn fit(available: usize) -> Self {
+        let mut layout = Layout {
+            name_width: NAME_WIDTH,
+            columns: COLUMNS.to_vec(),
+        };
+
+        let excess = layout.width().saturating_sub(available);
+        layout.name_width -= excess.min(NAME_WIDTH - MIN_NAME_WIDTH);
+        for column in DROP_ORDER {
+            if layout.width() <= available {
+                break;
+            }
+            layout.columns.retain(|c| *c != column);
+        }
+        // Hand back to the names whatever room the dropped columns freed.
+        let slack = available.saturating_sub(layout.width());
+        layout.name_width += slack.min(NAME_WIDTH - layout.name_width);
+
+        layout
+    }
+
+    fn width(&self) -> usize {
+        2 + self.name_width + self.columns.iter().map(|c| 2 + c.width()).sum::<usize>()
+    }
+
+    fn line(&self, name: &str, cells: impl Fn(Column) -> String) -> String {
+        let mut line = format!(
+            "│ {:<width$}",
+            truncate(name, self.name_width),
+            width = self.name_width
+        );
+        for &column in &self.columns {
+            line += &format!("  {:>width$}", cells(column), width = column.width());
+        }
+        line
+    }
+}
+
+/// Prints a table summarising every endpoint pinged in the run, with a row
+/// for each group of nodes and a line for all of them.
+pub fn print_summary(pb: &Printer, config: &'static Opts, responses: &[JobResponse]) {
+    let JobKind::Icmp(ref opts) = config.job else {
+        return;
+    };
+    let layout = Layout::fit(pb.fit(104));
+    let width = layout.width();
+
+    if responses.is_empty() {
+        pb.println(format!(
+            "{}",
+            "No results, every job failed".color(Color::Red)
+        ));
+        pb.println("");
+        return;
+    }
+
+    for endpoint in &opts.endpoints {
+        let mut groups: Vec<(String, Vec<&PerformIcmpResponseResultsItem>)> =
+            match config.summary_by {
+                SummaryGrouping::Region => config
+                    .regions
+                    .iter()
+                    .map(|region| (region.name(), vec![]))
+                    .collect(),
+                _ => vec![],
+            };
+        let mut all = vec![];
+
+        for response in responses {
+            let JobData::Icmp { response: icmp, .. } = &response.data else {
+                continue;
+            };
+            let key = group_name(config.summary_by, &response.region, icmp.node_info.as_ref());
+
+            for item in icmp.results.iter().filter(|i| i.endpoint == *endpoint) {
+                match groups.iter_mut().find(|(name, _)| *name == key) {
+                    Some((_, items)) => items.push(item),
+                    None => groups.push((key.clone(), vec![item])),
+                }
+                all.push(item);
+            }
+        }
+        if config.summary_by != SummaryGrouping::Region {
+            groups.sort_by(|(a, _), (b, _)| a.cmp(b));
+        }
+
+        print_border(pb, width);
+        pb.println(format!(
+            "│ SUMMARY {} by {}",
+            endpoint,
+            format!("{:?}", config.summary_by).to_lowercase()
+        ));
+        pb.println("│ ---");
+        pb.println(layout.line("", |c| c.header().to_string()));
+
+        for (name, items) in &groups {
+            print_row(
+                pb,
+                &layout,
+                name,
+                &GroupSummary::from_results(items.iter().copied()),
+            );
+        }
+
+        pb.println("│ ---");
+        print_row(pb, &layout, "All", &GroupSummary::from_results(all));
+        print_footer(pb, width);
+        pb.println("");
+    }
+}
+
+fn print_row(pb: &Printer, layout: &Layout, name: &str, summary: &GroupSummary) {
+    let line = layout.line(name, |c| c.value(summary));
+
+    if summary.successful < summary.nodes {
+        pb.println(format!("{}", line.color(Color::Red)));
+    } else {
+        pb.println(line);
+    }
+}
+
+fn group_name(grouping: SummaryGrouping, region: &EarthRegion, node: Option<&NodeInfo>) -> String {
+    let Some(node) = node else {
+        return match grouping {
+            SummaryGrouping::Region => region.name(),
+            _ => "Unknown".to_string(),
+        };
+    };
+
+    match grouping {
+        SummaryGrouping::Region => region.name(),
+        SummaryGrouping::Continent => node.continent_name().to_string(),
+        SummaryGrouping::Country => node.country().map_or(node.country_code.clone(), |c| {
+            c.iso_short_name().to_string()
+        }),
+        SummaryGrouping::Isp => node.isp.clone(),
+    }
+}
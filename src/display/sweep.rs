@@ -0,0 +1,190 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use colorful::{Color, Colorful};
+
+use crate::{
+    job::{JobData, JobResponse},
+    options::{EarthRegion, NodeIdentity},
+};
+
+use super::{
+    ping_display::{print_border, print_footer},
+    theme::Printer,
+};
+
+/// Collects the results of an ICMP payload size sweep so the size at which
+/// packet loss begins can be reported for every region and endpoint.
+#[derive(Debug)]
+pub struct SweepReport {
+    /// The `--timeout` every probe was sent with, or the node's own.
+    timeout: Option<u64>,
+    points: BTreeMap<(String, String), BTreeMap<(usize, SweepNode), SweepPoint>>,
+}
+
+/// The node a payload size was probed from, as `--node` takes it. Without
+/// `--node`, each size can land on a different node, so their results are
+/// kept apart.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct SweepNode(String);
+
+impl std::fmt::Display for SweepNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Default)]
+struct SweepPoint {
+    nodes: usize,
+    errors: usize,
+    packets_sent: f64,
+    packets_recv: f64,
+    rtt_total: f64,
+    responding_nodes: usize,
+}
+
+impl SweepPoint {
+    fn packet_loss(&self) -> f64 {
+        if self.packets_sent == 0.0 {
+            return 1.0;
+        }
+        1.0 - self.packets_recv / self.packets_sent
+    }
+
+    fn has_loss(&self) -> bool {
+        self.errors > 0 || self.packet_loss() > 0.0
+    }
+}
+
+impl SweepReport {
+    pub fn new(timeout: Option<u64>) -> Self {
+        Self {
+            timeout,
+            points: BTreeMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, job: &JobResponse) {
+        let JobData::Icmp {
+            payload_size,
+            ref response,
+        } = job.data
+        else {
+            return;
+        };
+
+        let node_info = response.node_info.as_ref();
+        for result in &response.results {
+            let node = SweepNode(
+                node_info.map_or("Unknown".to_string(), |n| NodeIdentity::from(n).to_string()),
+            );
+            let point = self
+                .points
+                .entry((region_name(&job.region), result.endpoint.clone()))
+                .or_default()
+                .entry((payload_size, node))
+                .or_default();
+
+            point.nodes += 1;
+            match &result.result {
+                Some(r) if result.error.is_none() => {
+                    point.packets_sent += r.packets_sent;
+                    point.packets_recv += r.packets_recv;
+                    if r.packets_recv > 0.0 {
+                        point.rtt_total += r.avg;
+                        point.responding_nodes += 1;
+                    }
+                }
+                _ => point.errors += 1,
+            }
+        }
+    }
+
+    pub fn print(&self, pb: &Printer) {
+        let width = 80;
+        let timeout = self
+            .timeout
+            .map_or("node default".to_string(), |t| format!("{t} ms"));
+
+        for ((region, endpoint), sizes) in &self.points {
+            print_border(pb, width);
+            pb.println(format!(
+                "│ SWEEP {} from {}, timeout {}",
+                endpoint, region, timeout
+            ));
+            pb.println("│ ---");
+            pb.println(format!(
+                "│ {:>6}  {:>5}  {:>7}  {:>12}  {:<7}  node",
+                "size", "nodes", "loss", "avg", ""
+            ));
+
+            let onset = sizes.iter().position(|(_, point)| point.has_loss());
+            for (i, ((size, node), point)) in sizes.iter().enumerate() {
+                let avg = if point.responding_nodes == 0 {
+                    "-".to_string()
+                } else {
+                    format!("{:.3} ms", point.rtt_total / point.responding_nodes as f64)
+                };
+                let marker = if Some(i) == onset { "◀ onset" } else { "" };
+                let line = format!(
+                    "│ {:>6}  {:>5}  {:>6.1}%  {:>12}  {:<7}  {}",
+                    size,
+                    point.nodes,
+                    point.packet_loss() * 100.0,
+                    avg,
+                    marker,
+                    node
+                );
+
+                if point.has_loss() {
+                    pb.println(format!("{}", line.color(Color::Red)));
+                } else {
+                    pb.println(line);
+                }
+            }
+
+            pb.println("│");
+            pb.println(format!("│ --- {} payload size sweep ---", endpoint));
+
+            let first_loss = sizes.iter().find(|(_, point)| point.has_loss());
+            let largest = sizes.keys().next_back().map_or(0, |(size, _)| *size);
+            match first_loss {
+                Some(((size, _), _)) => {
+                    let clean = sizes
+                        .iter()
+                        .take_while(|(_, point)| !point.has_loss())
+                        .last()
+                        .map_or("none".to_string(), |((size, _), _)| {
+                            format!("{} bytes", size)
+                        });
+                    let line = format!(
+                        "│ Packet loss begins at {} bytes (largest clean payload: {})",
+                        size, clean
+                    );
+                    pb.println(format!("{}", line.color(Color::Red)));
+                }
+                None => pb.println(format!("│ No packet loss up to {} bytes", largest)),
+            }
+
+            // Loss that starts on another node may have nothing to do with
+            // the payload size.
+            let nodes: BTreeSet<_> = sizes.keys().map(|(_, node)| node).collect();
+            if nodes.len() > 1 {
+                pb.println(format!(
+                    "│ Sizes were probed from {} different nodes; pass one of them to --node to sweep from it",
+                    nodes.len()
+                ));
+            }
+
+            print_footer(pb, width);
+            pb.println("");
+        }
+    }
+}
+
+fn region_name(region: &EarthRegion) -> String {
+    match region {
+        EarthRegion::Anywhere => "Anywhere".to_string(),
+        region => region.to_string(),
+    }
+}
@@ -0,0 +1,250 @@
+use std::io::{IsTerminal, Write};
+
+use colorful::{core::ColorInterface, Color};
+use console::{strip_ansi_codes, Term};
+use indicatif::ProgressBar;
+
+use crate::options::{Opts, OutputFormat};
+
+/// The colours RTTs are shown in, from fastest to slowest, split by
+/// `--rtt-thresholds`.
+pub(super) const RTT_COLOURS: [Color; 4] =
+    [Color::Green, Color::Yellow, Color::DarkOrange, Color::Red];
+
+/// The colours packet loss is shown in, split by `--loss-thresholds`.
+const LOSS_COLOURS: [Color; 3] = [Color::Green, Color::Yellow, Color::Red];
+
+/// Whether colours are wanted at all, going by `--plain` and the `NO_COLOR`
+/// convention.
+pub fn wants_colour(plain: bool) -> bool {
+    !plain && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+pub(super) fn rtt_colour(config: &Opts, avg: f64) -> Color {
+    RTT_COLOURS[config.rtt_thresholds.band(avg)]
+}
+
+/// Loss goes from 0 to 1, while the thresholds are percentages.
+pub(super) fn loss_colour(config: &Opts, packet_loss: f64) -> Color {
+    LOSS_COLOURS[config.loss_thresholds.band(packet_loss * 100.0)]
+}
+
+/// The colour of a result as a whole: its loss colour if it lost more than
+/// the first loss threshold allows, otherwise its RTT colour.
+pub(super) fn result_colour(config: &Opts, avg: Option<f64>, packet_loss: f64) -> Color {
+    match avg {
+        Some(avg) if config.loss_thresholds.band(packet_loss * 100.0) == 0 => {
+            rtt_colour(config, avg)
+        }
+        _ => loss_colour(config, packet_loss),
+    }
+}
+
+/// A colour as the dashboard draws it. Both use the same 256 colour palette,
+/// so a result looks the same in the dashboard as it does printed.
+pub(super) fn tui_colour(colour: Color) -> ratatui::style::Color {
+    ratatui::style::Color::Indexed(colour.to_color_str().parse().unwrap_or_default())
+}
+
+/// Cuts text down to `width` characters, ending in `…` when it's too long.
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(width - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Where display lines end up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    /// Above the progress bar, when both are on a terminal.
+    Bar,
+    /// Straight to stdout, eg. when piped to a file or another program.
+    Stdout,
+    /// Nowhere, as stdout is taken by another output format or the dashboard.
+    Hidden,
+}
+
+/// Prints display lines to wherever they belong, fitted to the terminal:
+/// without colour when stdout isn't a terminal or `NO_COLOR` is set, and in
+/// plain ASCII with `--plain`.
+pub struct Printer {
+    bar: ProgressBar,
+    target: Target,
+    colour: bool,
+    ascii: bool,
+    /// The width of the terminal stdout is attached to, if any.
+    columns: Option<usize>,
+}
+
+impl Printer {
+    pub fn new(bar: ProgressBar, config: &Opts) -> Self {
+        let terminal = std::io::stdout().is_terminal();
+        let target = if config.output != OutputFormat::Text || config.tui {
+            Target::Hidden
+        } else if terminal && !bar.is_hidden() {
+            Target::Bar
+        } else {
+            Target::Stdout
+        };
+
+        Self {
+            bar,
+            target,
+            colour: terminal && wants_colour(config.plain),
+            ascii: config.plain,
+            columns: terminal_columns(),
+        }
+    }
+
+    /// Prints straight to stdout, for commands without a progress bar.
+    pub fn stdout(plain: bool) -> Self {
+        Self {
+            bar: ProgressBar::hidden(),
+            target: Target::Stdout,
+            colour: std::io::stdout().is_terminal() && wants_colour(plain),
+            ascii: plain,
+            columns: terminal_columns(),
+        }
+    }
+
+    pub fn println(&self, line: impl AsRef<str>) {
+        match self.target {
+            Target::Bar => self.bar.println(self.render(line.as_ref())),
+            Target::Stdout => {
+                let _ = writeln!(std::io::stdout().lock(), "{}", self.render(line.as_ref()));
+            }
+            Target::Hidden => {}
+        }
+    }
+
+    /// Whether lines can be redrawn in place, as they're going to a terminal.
+    pub fn is_terminal(&self) -> bool {
+        self.target == Target::Bar
+    }
+
+    /// Hides the progress bar while the terminal is written to directly.
+    pub fn suspend<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.bar.suspend(f)
+    }
+
+    /// The given width, narrowed to fit the terminal.
+    pub fn fit(&self, width: usize) -> usize {
+        self.columns.map_or(width, |columns| width.min(columns))
+    }
+
+    /// Strips colours and non-ASCII characters from a line, if they're off.
+    pub fn render(&self, line: &str) -> String {
+        let line = if self.colour {
+            line.to_string()
+        } else {
+            strip_ansi_codes(line).into_owned()
+        };
+
+        if self.ascii {
+            line.chars().fold(String::new(), |mut ascii, c| {
+                push_ascii(&mut ascii, c);
+                ascii
+            })
+        } else {
+            line
+        }
+    }
+}
+
+/// The width of the terminal stdout is attached to, if any.
+fn terminal_columns() -> Option<usize> {
+    Term::stdout()
+        .size_checked()
+        .filter(|_| std::io::stdout().is_terminal())
+        .map(|(_, columns)| columns as usize)
+}
+
+/// Appends the closest ASCII to a character, or nothing for decoration like
+/// emoji.
+fn push_ascii(ascii: &mut String, c: char) {
+    match c {
+        c if c.is_ascii() => ascii.push(c),
+        '│' => ascii.push('|'),
+        '─' => ascii.push('-'),
+        '━' => ascii.push('='),
+        '┌' | '┐' | '└' | '┘' | '├' => ascii.push('+'),
+        '·' => ascii.push('-'),
+        // A single character, so truncated columns stay in line.
+        '…' => ascii.push('~'),
+        '±' => ascii.push_str("+/-"),
+        '●' => ascii.push('o'),
+        '▲' => ascii.push('^'),
+        '◀' => ascii.push('<'),
+        '✖' => ascii.push('x'),
+        '▁' | '▂' | '▃' => ascii.push('.'),
+        '▄' | '▅' => ascii.push(':'),
+        '▆' | '▇' => ascii.push('+'),
+        '█'..='▏' => ascii.push('#'),
+        // Land on the braille world map.
+        '\u{2800}' => ascii.push(' '),
+        '\u{2801}'..='\u{28ff}' => ascii.push('.'),
+        // Flags are pairs of regional indicators, one for each letter of
+        // the country code.
+        '\u{1f1e6}'..='\u{1f1ff}' => ascii.push(char::from(b'A' + (c as u32 - 0x1f1e6) as u8)),
+        // Degrees after the node coordinates.
+        '°' => {}
+        // Marks that take no room of their own, like the selector after an
+        // emoji or an accent written separately from its letter.
+        '\u{0300}'..='\u{036f}' | '\u{200b}'..='\u{200d}' | '\u{fe00}'..='\u{fe0f}' => {}
+        // Keep names like São Paulo readable, and anything else still one
+        // character wide.
+        c => ascii.push(fold_latin(c).unwrap_or('?')),
+    }
+}
+
+/// Accented Latin letters, grouped by the ASCII letter they're written as.
+const LATIN: [(&str, char); 38] = [
+    ("ÀÁÂÃÄÅĀĂĄ", 'A'),
+    ("àáâãäåāăą", 'a'),
+    ("ÇĆĈĊČ", 'C'),
+    ("çćĉċč", 'c'),
+    ("ĎĐ", 'D'),
+    ("ďđ", 'd'),
+    ("ÈÉÊËĒĔĖĘĚ", 'E'),
+    ("èéêëēĕėęě", 'e'),
+    ("ĜĞĠĢ", 'G'),
+    ("ĝğġģ", 'g'),
+    ("ĤĦ", 'H'),
+    ("ĥħ", 'h'),
+    ("ÌÍÎÏĨĪĬĮİ", 'I'),
+    ("ìíîïĩīĭįı", 'i'),
+    ("Ĵ", 'J'),
+    ("ĵ", 'j'),
+    ("Ķ", 'K'),
+    ("ķ", 'k'),
+    ("ĹĻĽĿŁ", 'L'),
+    ("ĺļľŀł", 'l'),
+    ("ÑŃŅŇ", 'N'),
+    ("ñńņň", 'n'),
+    ("ÒÓÔÕÖØŌŎŐ", 'O'),
+    ("òóôõöøōŏő", 'o'),
+    ("ŔŖŘ", 'R'),
+    ("ŕŗř", 'r'),
+    ("ŚŜŞŠȘ", 'S'),
+    ("śŝşšșß", 's'),
+    ("ŢŤŦȚ", 'T'),
+    ("ţťŧț", 't'),
+    ("ÙÚÛÜŨŪŬŮŰŲ", 'U'),
+    ("ùúûüũūŭůűų", 'u'),
+    ("Ŵ", 'W'),
+    ("ŵ", 'w'),
+    ("ÝŸŶ", 'Y'),
+    ("ýÿŷ", 'y'),
+    ("ŹŻŽ", 'Z'),
+    ("źżž", 'z'),
+];
+
+fn fold_latin(c: char) -> Option<char> {
+    LATIN
+        .iter()
+        .find(|(accented, _)| accented.contains(c))
+        .map(|&(_, plain)| plain)
+}
@@ -0,0 +1,329 @@
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    widgets::TableState,
+};
+
+use crate::{
+    display::{order::cmp_missing_last, progress::Update},
+    job::JobResponse,
+    models::types::PerformIcmpResponseResultsItem,
+    options::{EarthRegion, JobKind, Opts},
+    output::{icmp_rows, IcmpRow},
+};
+
+/// The most errors kept for the error pane.
+const MAX_ERRORS: usize = 200;
+
+/// An [`IcmpRow`] of the table, kept along with what it's searched and
+/// sorted by.
+pub(super) struct NodeRow {
+    pub region: EarthRegion,
+    pub payload_size: usize,
+    pub flag: String,
+    pub country: String,
+    pub continent: String,
+    pub city: String,
+    pub isp: String,
+    pub item: PerformIcmpResponseResultsItem,
+}
+
+impl NodeRow {
+    /// The node's average RTT, if any replies came back.
+    pub fn avg(&self) -> Option<f64> {
+        self.item.rtt_avg()
+    }
+
+    pub fn packet_loss(&self) -> Option<f64> {
+        self.item.packet_loss()
+    }
+
+    pub fn failed(&self) -> bool {
+        self.packet_loss().is_none_or(|loss| loss >= 1.0)
+    }
+
+    fn matches(&self, search: &str) -> bool {
+        let search = search.to_lowercase();
+        [
+            self.region.name().as_str(),
+            &self.item.endpoint,
+            &self.country,
+            &self.continent,
+            &self.city,
+            &self.isp,
+            self.item.result.as_ref().map_or("", |r| &r.ip_address),
+        ]
+        .iter()
+        .any(|field| field.to_lowercase().contains(&search))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum SortKey {
+    Received,
+    Region,
+    Rtt,
+    Loss,
+    Country,
+    Isp,
+}
+
+impl SortKey {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortKey::Received => "received",
+            SortKey::Region => "region",
+            SortKey::Rtt => "rtt",
+            SortKey::Loss => "loss",
+            SortKey::Country => "country",
+            SortKey::Isp => "isp",
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            SortKey::Received => SortKey::Region,
+            SortKey::Region => SortKey::Rtt,
+            SortKey::Rtt => SortKey::Loss,
+            SortKey::Loss => SortKey::Country,
+            SortKey::Country => SortKey::Isp,
+            SortKey::Isp => SortKey::Received,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Filter {
+    All,
+    Lossy,
+    Failures,
+}
+
+impl Filter {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Filter::All => "all",
+            Filter::Lossy => "loss > 0",
+            Filter::Failures => "failures",
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            Filter::All => Filter::Lossy,
+            Filter::Lossy => Filter::Failures,
+            Filter::Failures => Filter::All,
+        }
+    }
+
+    fn keeps(&self, row: &NodeRow) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Lossy => row.packet_loss().is_none_or(|loss| loss > 0.0),
+            Filter::Failures => row.failed(),
+        }
+    }
+}
+
+/// What a key press asks the dashboard's event loop to do.
+pub(super) enum Action {
+    None,
+    Quit,
+    Rerun(EarthRegion),
+}
+
+/// Everything the dashboard shows, updated as results land.
+pub(super) struct Dashboard {
+    pub config: &'static Opts,
+    pub responses: Vec<JobResponse>,
+    pub rows: Vec<NodeRow>,
+    pub errors: Vec<String>,
+    pub received: usize,
+    pub expected: usize,
+    pub sort: SortKey,
+    pub descending: bool,
+    pub filter: Filter,
+    pub search: String,
+    pub searching: bool,
+    pub table: TableState,
+    pub status: Option<String>,
+}
+
+impl Dashboard {
+    pub fn new(config: &'static Opts) -> Self {
+        Self {
+            config,
+            responses: vec![],
+            rows: vec![],
+            errors: vec![],
+            received: 0,
+            expected: config.job_count().unwrap_or_default(),
+            sort: SortKey::Received,
+            descending: false,
+            filter: Filter::All,
+            search: String::new(),
+            searching: false,
+            table: TableState::default().with_selected(0),
+            status: None,
+        }
+    }
+
+    pub fn record(&mut self, update: Update) {
+        self.received += 1;
+
+        let response = match update {
+            Update::Job(response) => response,
+            Update::Failed { region, error } => {
+                self.push_error(format!("{}: {error}", region.name()));
+                return;
+            }
+            // The dashboard can't be combined with --watch.
+            Update::CycleFinished => return,
+        };
+
+        for IcmpRow {
+            payload_size,
+            node_info,
+            item,
+            ..
+        } in icmp_rows([&response])
+        {
+            let country = node_info.and_then(|n| n.country());
+
+            let row = NodeRow {
+                region: response.region.clone(),
+                payload_size,
+                flag: country
+                    .as_ref()
+                    .map(|c| c.emoji().to_string())
+                    .unwrap_or_default(),
+                country: match (&country, node_info) {
+                    (Some(country), _) => country.iso_short_name().to_string(),
+                    (None, Some(n)) => n.country_code.clone(),
+                    (None, None) => "Unknown".to_string(),
+                },
+                continent: node_info
+                    .map_or("Unknown", |n| n.continent_name())
+                    .to_string(),
+                city: node_info.map(|n| n.city.clone()).unwrap_or_default(),
+                isp: node_info.map(|n| n.isp.clone()).unwrap_or_default(),
+                item: item.clone(),
+            };
+
+            if let Some(error) = &item.error {
+                self.push_error(format!(
+                    "{} {}: {error}",
+                    response.region.name(),
+                    item.endpoint
+                ));
+            } else if row.failed() {
+                self.push_error(format!(
+                    "{} {}: 100% packet loss from {}, {}",
+                    response.region.name(),
+                    item.endpoint,
+                    row.isp,
+                    row.country
+                ));
+            }
+            self.rows.push(row);
+        }
+
+        self.responses.push(response);
+    }
+
+    fn push_error(&mut self, error: String) {
+        self.errors.push(error);
+        if self.errors.len() > MAX_ERRORS {
+            self.errors.remove(0);
+        }
+    }
+
+    /// The rows that pass the filter and search, in the chosen order.
+    pub fn visible(&self) -> Vec<&NodeRow> {
+        let mut rows: Vec<&NodeRow> = self
+            .rows
+            .iter()
+            .filter(|row| self.filter.keeps(row))
+            .filter(|row| self.search.is_empty() || row.matches(&self.search))
+            .collect();
+
+        match self.sort {
+            SortKey::Received => {}
+            SortKey::Region => rows.sort_by_key(|row| row.region.name()),
+            SortKey::Rtt => rows.sort_by(|a, b| cmp_missing_last(a.avg(), b.avg())),
+            SortKey::Loss => {
+                rows.sort_by(|a, b| cmp_missing_last(a.packet_loss(), b.packet_loss()))
+            }
+            SortKey::Country => rows.sort_by(|a, b| a.country.cmp(&b.country)),
+            SortKey::Isp => rows.sort_by(|a, b| a.isp.cmp(&b.isp)),
+        }
+        if self.descending {
+            rows.reverse();
+        }
+
+        rows
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+
+        if self.searching {
+            match key.code {
+                KeyCode::Enter => self.searching = false,
+                KeyCode::Esc => {
+                    self.searching = false;
+                    self.search.clear();
+                }
+                KeyCode::Backspace => {
+                    self.search.pop();
+                }
+                KeyCode::Char(c) => self.search.push(c),
+                _ => {}
+            }
+            self.table.select(Some(0));
+            return Action::None;
+        }
+
+        let rows = self.visible().len();
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Down | KeyCode::Char('j') => self.table.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.table.select_previous(),
+            KeyCode::PageDown => self.table.scroll_down_by(10),
+            KeyCode::PageUp => self.table.scroll_up_by(10),
+            KeyCode::Home | KeyCode::Char('g') => self.table.select_first(),
+            KeyCode::End | KeyCode::Char('G') => self.table.select(rows.checked_sub(1)),
+            KeyCode::Char('s') => self.sort = self.sort.next(),
+            KeyCode::Char('r') => self.descending = !self.descending,
+            KeyCode::Char('f') => {
+                self.filter = self.filter.next();
+                self.table.select(Some(0));
+            }
+            KeyCode::Char('/') => self.searching = true,
+            KeyCode::Enter | KeyCode::Char('R') => return self.rerun_selected(),
+            _ => {}
+        }
+
+        Action::None
+    }
+
+    fn rerun_selected(&mut self) -> Action {
+        let Some(region) = self
+            .table
+            .selected()
+            .and_then(|index| self.visible().get(index).map(|row| row.region.clone()))
+        else {
+            return Action::None;
+        };
+
+        let jobs_per_region = match &self.config.job {
+            JobKind::Icmp(opts) => opts.payload_sizes().len(),
+            _ => 1,
+        };
+        self.expected += self.config.attempts * jobs_per_region;
+        self.status = Some(format!("Running {} again", region.name()));
+
+        Action::Rerun(region)
+    }
+}
@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use color_eyre::eyre::Result;
+use ratatui::{
+    crossterm::event::{self, Event, KeyEventKind},
+    DefaultTerminal,
+};
+use tokio::sync::mpsc::{Receiver, UnboundedSender};
+
+use crate::{
+    job::JobResponse,
+    options::{EarthRegion, Opts},
+};
+
+use super::progress::Update;
+use dashboard::{Action, Dashboard};
+
+mod dashboard;
+mod ui;
+
+/// How often the dashboard checks for key presses while no results land.
+const TICK: Duration = Duration::from_millis(100);
+
+/// Shows the full-screen dashboard for `--tui` until it's closed, returning
+/// every response received by then.
+pub(super) async fn run(
+    config: &'static Opts,
+    rx: &mut Receiver<Update>,
+    reruns: UnboundedSender<EarthRegion>,
+) -> Result<Vec<JobResponse>> {
+    let mut dashboard = Dashboard::new(config);
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut dashboard, rx, &reruns).await;
+    ratatui::restore();
+
+    // Stop the scheduler sending any more jobs, then keep whatever is
+    // already on its way.
+    drop(reruns);
+    rx.close();
+    while let Some(update) = rx.recv().await {
+        dashboard.record(update);
+    }
+
+    result.map(|_| dashboard.responses)
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    dashboard: &mut Dashboard,
+    rx: &mut Receiver<Update>,
+    reruns: &UnboundedSender<EarthRegion>,
+) -> Result<()> {
+    let mut ticker = tokio::time::interval(TICK);
+
+    loop {
+        terminal.draw(|frame| ui::draw(frame, dashboard))?;
+
+        tokio::select! {
+            Some(update) = rx.recv() => dashboard.record(update),
+            _ = ticker.tick() => {}
+        }
+
+        while event::poll(Duration::ZERO)? {
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match dashboard.handle_key(key) {
+                Action::None => {}
+                Action::Quit => return Ok(()),
+                Action::Rerun(region) => {
+                    let _ = reruns.send(region);
+                }
+            }
+        }
+    }
+}
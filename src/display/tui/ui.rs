@@ -0,0 +1,309 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Bar, BarChart, BarGroup, Block, Cell, List, ListItem, Paragraph, Row, Table},
+    Frame,
+};
+
+use crate::{
+    display::theme::{self, loss_colour, tui_colour},
+    options::{JobKind, Opts},
+    stats::GroupSummary,
+};
+
+use super::dashboard::{Dashboard, NodeRow};
+
+/// The upper bound of each histogram bucket, in milliseconds.
+const HISTOGRAM_BUCKETS: [f64; 7] = [50.0, 100.0, 150.0, 200.0, 300.0, 500.0, f64::INFINITY];
+
+pub(super) fn draw(frame: &mut Frame, dashboard: &mut Dashboard) {
+    let [header, body, errors, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(8),
+        Constraint::Length(7),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [table, side] =
+        Layout::horizontal([Constraint::Min(60), Constraint::Length(42)]).areas(body);
+
+    let continents = continent_summaries(dashboard);
+    let [continent_pane, histogram] = Layout::vertical([
+        Constraint::Length(continents.len() as u16 + 3),
+        Constraint::Min(6),
+    ])
+    .areas(side);
+
+    draw_header(frame, header, dashboard);
+    draw_table(frame, table, dashboard);
+    draw_continents(frame, continent_pane, &continents);
+    draw_histogram(frame, histogram, dashboard);
+    draw_errors(frame, errors, dashboard);
+    draw_footer(frame, footer, dashboard);
+}
+
+fn draw_header(frame: &mut Frame, area: Rect, dashboard: &Dashboard) {
+    let targets = match &dashboard.config.job {
+        JobKind::Icmp(opts) => opts.endpoints.join(", "),
+        _ => String::new(),
+    };
+
+    let mut spans = vec![
+        Span::from(format!(" bping {targets} ")).bold(),
+        Span::from(format!(
+            "· {}/{} jobs · sort {} {} · filter {}",
+            dashboard.received,
+            dashboard.expected,
+            dashboard.sort.name(),
+            if dashboard.descending { "↓" } else { "↑" },
+            dashboard.filter.name()
+        )),
+    ];
+    if !dashboard.search.is_empty() || dashboard.searching {
+        spans.push(Span::from(format!(" · search {}", dashboard.search)));
+        if dashboard.searching {
+            spans.push(Span::from("_").slow_blink());
+        }
+    }
+    if let Some(status) = &dashboard.status {
+        spans.push(Span::from(format!(" · {status}")).cyan());
+    }
+
+    frame.render_widget(Line::from(spans), area);
+}
+
+fn draw_table(frame: &mut Frame, area: Rect, dashboard: &mut Dashboard) {
+    let multiple_endpoints =
+        matches!(&dashboard.config.job, JobKind::Icmp(opts) if opts.endpoints.len() > 1);
+    let sweep = matches!(&dashboard.config.job, JobKind::Icmp(opts) if opts.sweep.is_some());
+
+    // Wide enough for the longest region in the run, so names like "North
+    // America" aren't cut off.
+    let region_width = dashboard
+        .config
+        .regions
+        .iter()
+        .map(|region| region.name().chars().count())
+        .chain(["Region".len()])
+        .max()
+        .unwrap_or_default();
+
+    let mut header = vec!["Region"];
+    let mut widths = vec![Constraint::Length(region_width as u16)];
+    if multiple_endpoints {
+        header.push("Endpoint");
+        widths.push(Constraint::Length(16));
+    }
+    if sweep {
+        header.push("Size");
+        widths.push(Constraint::Length(5));
+    }
+    header.extend(["Country", "City", "ISP", "IP", "Loss", "Min", "Avg", "Max"]);
+    widths.extend([
+        Constraint::Length(16),
+        Constraint::Length(12),
+        Constraint::Min(10),
+        Constraint::Length(15),
+        Constraint::Length(5),
+        Constraint::Length(9),
+        Constraint::Length(9),
+        Constraint::Length(9),
+    ]);
+
+    let visible = dashboard.visible();
+    let title = format!(" Nodes {}/{} ", visible.len(), dashboard.rows.len());
+    let rows: Vec<Row> = visible
+        .iter()
+        .map(|row| node_row(dashboard.config, row, multiple_endpoints, sweep))
+        .collect();
+
+    let table = Table::new(rows, widths)
+        .header(Row::new(header).bold())
+        .block(Block::bordered().title(title))
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(table, area, &mut dashboard.table);
+}
+
+fn node_row(config: &Opts, row: &NodeRow, multiple_endpoints: bool, sweep: bool) -> Row<'static> {
+    let mut cells = vec![Cell::from(row.region.name())];
+    if multiple_endpoints {
+        cells.push(Cell::from(row.item.endpoint.clone()));
+    }
+    if sweep {
+        cells.push(Cell::from(row.payload_size.to_string()));
+    }
+    cells.extend([
+        Cell::from(format!("{} {}", row.flag, row.country)),
+        Cell::from(row.city.clone()),
+        Cell::from(row.isp.clone()),
+        Cell::from(
+            row.item
+                .result
+                .as_ref()
+                .map(|r| r.ip_address.clone())
+                .unwrap_or_default(),
+        ),
+    ]);
+
+    if let Some(error) = &row.item.error {
+        cells.push(Cell::from(format!("error: {error}")));
+        return Row::new(cells).red();
+    }
+
+    let ms = |value: f64| Cell::from(format!("{value:.1} ms"));
+    match (&row.item.result, row.avg()) {
+        (Some(result), Some(avg)) => {
+            cells.push(
+                Cell::from(format!("{:.0}%", result.packet_loss * 100.0))
+                    .fg(tui_colour(loss_colour(config, result.packet_loss))),
+            );
+            cells.extend([
+                ms(result.min),
+                ms(avg).fg(rtt_colour(config, avg)),
+                ms(result.max),
+            ]);
+            Row::new(cells)
+        }
+        _ => {
+            cells.push(Cell::from("100%"));
+            Row::new(cells).red()
+        }
+    }
+}
+
+fn continent_summaries(dashboard: &Dashboard) -> Vec<(String, GroupSummary)> {
+    let mut continents: Vec<&str> = dashboard
+        .rows
+        .iter()
+        .map(|r| r.continent.as_str())
+        .collect();
+    continents.sort();
+    continents.dedup();
+
+    continents
+        .into_iter()
+        .map(|continent| {
+            let items = dashboard
+                .rows
+                .iter()
+                .filter(|r| r.continent == continent)
+                .map(|r| &r.item);
+            (continent.to_string(), GroupSummary::from_results(items))
+        })
+        .collect()
+}
+
+fn draw_continents(frame: &mut Frame, area: Rect, continents: &[(String, GroupSummary)]) {
+    let ms = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{v:.0} ms"));
+
+    let rows = continents.iter().map(|(name, summary)| {
+        let row = Row::new([
+            name.clone(),
+            summary.nodes.to_string(),
+            format!("{:.0}%", summary.success_rate() * 100.0),
+            ms(summary.rtt_p50),
+            ms(summary.rtt_p95),
+            summary
+                .packet_loss
+                .map_or("-".to_string(), |l| format!("{:.0}%", l * 100.0)),
+        ]);
+        if summary.successful < summary.nodes {
+            row.red()
+        } else {
+            row
+        }
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(10),
+            Constraint::Length(5),
+            Constraint::Length(4),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(4),
+        ],
+    )
+    .header(Row::new(["Continent", "Nodes", "OK", "p50", "p95", "Loss"]).bold())
+    .block(Block::bordered().title(" Continents "));
+    frame.render_widget(table, area);
+}
+
+fn draw_histogram(frame: &mut Frame, area: Rect, dashboard: &Dashboard) {
+    let mut counts = [0u64; HISTOGRAM_BUCKETS.len()];
+    let mut failed = 0;
+    for row in &dashboard.rows {
+        match row.avg() {
+            Some(avg) => {
+                let bucket = HISTOGRAM_BUCKETS.iter().position(|max| avg < *max);
+                counts[bucket.unwrap_or(HISTOGRAM_BUCKETS.len() - 1)] += 1;
+            }
+            None => failed += 1,
+        }
+    }
+
+    let mut lower = 0.0;
+    let mut bars: Vec<Bar> = HISTOGRAM_BUCKETS
+        .iter()
+        .zip(counts)
+        .map(|(max, count)| {
+            let label = if max.is_finite() {
+                format!("{lower:>3.0}-{max:<3.0}")
+            } else {
+                format!("{lower:>3.0}+   ")
+            };
+            // Coloured like the fastest RTTs that land in the bucket.
+            let colour = rtt_colour(dashboard.config, max.min(lower + 1.0));
+            lower = *max;
+            Bar::default()
+                .value(count)
+                .label(Line::from(label))
+                .style(Style::new().fg(colour))
+        })
+        .collect();
+    bars.push(
+        Bar::default()
+            .value(failed)
+            .label(Line::from("failed "))
+            .style(Style::new().fg(Color::DarkGray)),
+    );
+
+    let chart = BarChart::default()
+        .direction(Direction::Horizontal)
+        .bar_width(1)
+        .bar_gap(0)
+        .data(BarGroup::default().bars(&bars))
+        .block(Block::bordered().title(" Avg RTT (ms) "));
+    frame.render_widget(chart, area);
+}
+
+fn draw_errors(frame: &mut Frame, area: Rect, dashboard: &Dashboard) {
+    let shown = area.height.saturating_sub(2) as usize;
+    let items: Vec<ListItem> = dashboard
+        .errors
+        .iter()
+        .rev()
+        .take(shown)
+        .map(|error| ListItem::new(error.as_str()).red())
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::bordered().title(format!(" Errors {} ", dashboard.errors.len())));
+    frame.render_widget(list, area);
+}
+
+fn draw_footer(frame: &mut Frame, area: Rect, dashboard: &Dashboard) {
+    let keys = if dashboard.searching {
+        " type to search · enter keep · esc clear"
+    } else {
+        " q quit · ↑↓ select · s sort · r reverse · f filter · / search · enter run region again"
+    };
+    frame.render_widget(Paragraph::new(keys).dark_gray(), area);
+}
+
+/// The RTT colour of the printed output, split by `--rtt-thresholds`.
+fn rtt_colour(config: &Opts, avg: f64) -> Color {
+    tui_colour(theme::rtt_colour(config, avg))
+}
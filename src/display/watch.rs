@@ -0,0 +1,194 @@
+use colorful::{Color, Colorful};
+use console::Term;
+
+use crate::{
+    job::{JobData, JobResponse},
+    models::NodeInfo,
+    options::Opts,
+};
+
+use super::{
+    map::{Marker, WorldMap, MAP_WIDTH},
+    theme::{result_colour, Printer},
+};
+
+/// Rolling per-endpoint loss and latency for `--watch`, redrawn in place after
+/// every result like mtr.
+#[derive(Debug, Default)]
+pub struct WatchTable {
+    rows: Vec<(String, WatchRow)>,
+    node_info: Option<NodeInfo>,
+    cycles: usize,
+    drawn_lines: usize,
+}
+
+#[derive(Debug, Default)]
+struct WatchRow {
+    packets_sent: f64,
+    packets_recv: f64,
+    errors: usize,
+    last: Option<f64>,
+    best: Option<f64>,
+    worst: Option<f64>,
+    rtt_total: f64,
+    rtt_squares_total: f64,
+}
+
+impl WatchRow {
+    fn packet_loss(&self) -> f64 {
+        if self.packets_sent == 0.0 {
+            return 1.0;
+        }
+        1.0 - self.packets_recv / self.packets_sent
+    }
+
+    fn avg(&self) -> Option<f64> {
+        (self.packets_recv > 0.0).then(|| self.rtt_total / self.packets_recv)
+    }
+
+    /// Pools the standard deviation reported for each cycle into one across
+    /// every reply received so far.
+    fn std_dev(&self) -> Option<f64> {
+        let avg = self.avg()?;
+        let variance = self.rtt_squares_total / self.packets_recv - avg * avg;
+        Some(variance.max(0.0).sqrt())
+    }
+}
+
+impl WatchTable {
+    pub fn record(&mut self, job: &JobResponse) {
+        let JobData::Icmp { response, .. } = &job.data else {
+            return;
+        };
+
+        if let Some(node_info) = &response.node_info {
+            self.node_info = Some(node_info.clone());
+        }
+
+        for result in &response.results {
+            let index = match self.rows.iter().position(|(e, _)| *e == result.endpoint) {
+                Some(index) => index,
+                None => {
+                    self.rows
+                        .push((result.endpoint.clone(), WatchRow::default()));
+                    self.rows.len() - 1
+                }
+            };
+            let row = &mut self.rows[index].1;
+
+            let Some(r) = result.result.as_ref().filter(|_| result.error.is_none()) else {
+                row.errors += 1;
+                continue;
+            };
+
+            row.packets_sent += r.packets_sent;
+            row.packets_recv += r.packets_recv;
+            if r.packets_recv > 0.0 {
+                row.last = Some(r.avg);
+                row.best = Some(row.best.map_or(r.min, |b| b.min(r.min)));
+                row.worst = Some(row.worst.map_or(r.max, |w| w.max(r.max)));
+                row.rtt_total += r.avg * r.packets_recv;
+                row.rtt_squares_total += (r.std_dev * r.std_dev + r.avg * r.avg) * r.packets_recv;
+            } else {
+                row.last = None;
+            }
+        }
+    }
+
+    pub fn finish_cycle(&mut self) {
+        self.cycles += 1;
+    }
+
+    pub fn draw(&mut self, pb: &Printer, config: &'static Opts) {
+        let lines = self.lines(pb, config);
+
+        // Without a terminal to redraw in place, each update follows the last.
+        if !pb.is_terminal() {
+            for line in &lines {
+                pb.println(line);
+            }
+            pb.println("");
+            return;
+        }
+
+        pb.suspend(|| {
+            let term = Term::stdout();
+            let _ = term.clear_last_lines(self.drawn_lines);
+            for line in &lines {
+                let _ = term.write_line(&pb.render(line));
+            }
+        });
+
+        self.drawn_lines = lines.len();
+    }
+
+    fn lines(&self, pb: &Printer, config: &'static Opts) -> Vec<String> {
+        let node = config
+            .node
+            .as_ref()
+            .map_or("any node".to_string(), |node| node.to_string());
+        let origin = match &self.node_info {
+            Some(info) => format!(
+                "{}, {}, {} via {}",
+                info.city, info.region_name, info.country_code, info.isp
+            ),
+            None => "waiting for first result".to_string(),
+        };
+
+        let endpoint_width = self
+            .rows
+            .iter()
+            .map(|(e, _)| e.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("Endpoint".len());
+
+        let mut lines = vec![
+            format!("Watching from node {} ({})", node, origin),
+            format!("Cycles: {}", self.cycles),
+            String::new(),
+            format!(
+                "{:<endpoint_width$}  {:>6}  {:>5}  {:>8}  {:>8}  {:>8}  {:>8}  {:>7}",
+                "Endpoint", "Loss%", "Snt", "Last", "Avg", "Best", "Wrst", "StDev"
+            ),
+        ];
+
+        let ms = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{:.2}", v));
+        for (endpoint, row) in &self.rows {
+            let line = format!(
+                "{:<endpoint_width$}  {:>5.1}%  {:>5}  {:>8}  {:>8}  {:>8}  {:>8}  {:>7}",
+                endpoint,
+                row.packet_loss() * 100.0,
+                row.packets_sent,
+                ms(row.last),
+                ms(row.avg()),
+                ms(row.best),
+                ms(row.worst),
+                ms(row.std_dev()),
+            );
+
+            let colour = if row.errors > 0 {
+                Color::Red
+            } else {
+                result_colour(config, row.avg(), row.packet_loss())
+            };
+            lines.push(format!("{}", line.color(colour)));
+        }
+
+        if let (true, Some(info)) = (config.map, &self.node_info) {
+            let mut map = WorldMap::new(pb.fit(MAP_WIDTH), config);
+            for (_, row) in &self.rows {
+                map.plot(
+                    info.lat,
+                    info.lon,
+                    Marker::new(row.avg(), Some(row.packet_loss())),
+                );
+            }
+            lines.push(String::new());
+            lines.extend(map.lines());
+            lines.push(map.legend());
+        }
+
+        lines
+    }
+}
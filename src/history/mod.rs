@@ -0,0 +1,419 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::{eyre, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::{
+    job::JobData,
+    models::{types::PerformIcmpResponseResultsItemResult, NodeInfo},
+    options::{EarthRegion, Opts},
+    output::RunRecord,
+};
+
+mod print;
+
+pub use print::run_command;
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    started_at TEXT NOT NULL,
+    finished_at TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    targets TEXT NOT NULL,
+    regions TEXT NOT NULL,
+    parameters TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS results (
+    id INTEGER PRIMARY KEY,
+    run_id INTEGER NOT NULL REFERENCES runs (id) ON DELETE CASCADE,
+    received_at TEXT NOT NULL,
+    region TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    endpoint TEXT NOT NULL,
+    payload_size INTEGER,
+    duration REAL,
+    country_code TEXT,
+    continent_code TEXT,
+    region_name TEXT,
+    city TEXT,
+    isp TEXT,
+    network_type TEXT,
+    operating_system TEXT,
+    lat REAL,
+    lon REAL,
+    ip_address TEXT,
+    packets_sent REAL,
+    packets_recv REAL,
+    packet_loss REAL,
+    rtt_min REAL,
+    rtt_avg REAL,
+    rtt_max REAL,
+    rtt_std_dev REAL,
+    error TEXT,
+    result TEXT
+);
+
+CREATE INDEX IF NOT EXISTS results_run ON results (run_id);
+CREATE INDEX IF NOT EXISTS results_endpoint ON results (endpoint, kind);
+"#;
+
+/// Whether a stored result counts as the node reaching the endpoint.
+const REACHABLE: &str = "(error IS NULL AND COALESCE(packet_loss, 0) < 1)";
+
+/// Every run bping makes, kept in a SQLite database in the user's data
+/// directory so past runs can be looked at without sending the jobs again.
+pub struct HistoryStore {
+    conn: Connection,
+}
+
+/// A run along with the aggregates of its results.
+#[derive(Debug)]
+pub struct RunSummary {
+    pub id: i64,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub kind: String,
+    pub targets: String,
+    pub regions: String,
+    pub results: i64,
+    pub reachable: i64,
+    pub rtt_min: Option<f64>,
+    pub rtt_avg: Option<f64>,
+    pub rtt_max: Option<f64>,
+    pub packet_loss: Option<f64>,
+}
+
+impl RunSummary {
+    /// Reads a run selected along with its [`aggregate_columns`].
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            started_at: row.get(1)?,
+            finished_at: row.get(2)?,
+            kind: row.get(3)?,
+            targets: row.get(4)?,
+            regions: row.get(5)?,
+            results: row.get(6)?,
+            reachable: row.get(7)?,
+            rtt_min: row.get(8)?,
+            rtt_avg: row.get(9)?,
+            rtt_max: row.get(10)?,
+            packet_loss: row.get(11)?,
+        })
+    }
+}
+
+/// A single endpoint probed by a single node in a past run.
+#[derive(Debug)]
+pub struct StoredResult {
+    pub region: String,
+    pub endpoint: String,
+    pub payload_size: Option<i64>,
+    pub country_code: Option<String>,
+    pub city: Option<String>,
+    pub isp: Option<String>,
+    pub network_type: Option<String>,
+    pub ip_address: Option<String>,
+    pub packet_loss: Option<f64>,
+    pub rtt_min: Option<f64>,
+    pub rtt_avg: Option<f64>,
+    pub rtt_max: Option<f64>,
+    pub duration: Option<f64>,
+    pub error: Option<String>,
+}
+
+/// A result about to be saved, in the shape every kind of job shares.
+struct NewResult<'a> {
+    endpoint: &'a str,
+    payload_size: Option<usize>,
+    duration: Option<f64>,
+    error: Option<&'a str>,
+    icmp: Option<&'a PerformIcmpResponseResultsItemResult>,
+    result: Option<String>,
+}
+
+macro_rules! new_results {
+    ($response:expr) => {
+        $response
+            .results
+            .iter()
+            .map(|item| {
+                Ok(NewResult {
+                    endpoint: &item.endpoint,
+                    payload_size: None,
+                    duration: item.duration,
+                    error: item.error.as_deref(),
+                    icmp: None,
+                    result: item
+                        .result
+                        .as_ref()
+                        .map(serde_json::to_string)
+                        .transpose()?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+}
+
+impl HistoryStore {
+    /// Opens the history database, creating it if this is the first run.
+    pub fn open() -> Result<Self> {
+        let path = Self::path()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+
+        let conn = Connection::open(&path)
+            .with_context(|| format!("Failed to open history database {}", path.display()))?;
+        // SQLite leaves foreign keys off unless asked, and results are
+        // deleted along with their run.
+        conn.pragma_update(None, "foreign_keys", true)
+            .context("Failed to enable foreign keys in the history database")?;
+        conn.execute_batch(SCHEMA)
+            .context("Failed to create history tables")?;
+
+        Ok(Self { conn })
+    }
+
+    fn path() -> Result<PathBuf> {
+        let dir = dirs::data_dir().ok_or_else(|| eyre!("Unable to find a data directory"))?;
+        Ok(dir.join("bping").join("history.sqlite3"))
+    }
+
+    /// Saves a finished run and every node result in it, returning the run's ID.
+    pub fn save_run(&mut self, config: &Opts, run: &RunRecord) -> Result<i64> {
+        let tx = self.conn.transaction()?;
+
+        let regions: Vec<String> = config.regions.iter().map(EarthRegion::name).collect();
+        tx.execute(
+            "INSERT INTO runs (started_at, finished_at, kind, targets, regions, parameters)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                run.started_at,
+                run.finished_at,
+                config.job.name(),
+                config.job.targets(),
+                regions.join(", "),
+                serde_json::to_string(config)?,
+            ],
+        )?;
+        let run_id = tx.last_insert_rowid();
+
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO results (
+                    run_id, received_at, region, kind, endpoint, payload_size, duration,
+                    country_code, continent_code, region_name, city, isp, network_type,
+                    operating_system, lat, lon, ip_address, packets_sent, packets_recv,
+                    packet_loss, rtt_min, rtt_avg, rtt_max, rtt_std_dev, error, result
+                ) VALUES (
+                    ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
+                    ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26
+                )",
+            )?;
+
+            for response in &run.responses {
+                let (kind, node_info, results): (_, Option<NodeInfo>, Vec<NewResult>) =
+                    match &response.data {
+                        JobData::Icmp {
+                            payload_size,
+                            response,
+                        } => (
+                            "icmp",
+                            response.node_info.clone(),
+                            response
+                                .results
+                                .iter()
+                                .map(|item| NewResult {
+                                    endpoint: &item.endpoint,
+                                    payload_size: Some(*payload_size),
+                                    duration: item.duration,
+                                    error: item.error.as_deref(),
+                                    icmp: item.result.as_ref(),
+                                    result: None,
+                                })
+                                .collect(),
+                        ),
+                        JobData::Dns(response) => (
+                            "dns",
+                            response.node_info.clone().map(Into::into),
+                            new_results!(response),
+                        ),
+                        JobData::Http(response) => (
+                            "http",
+                            response.node_info.clone().map(Into::into),
+                            new_results!(response),
+                        ),
+                        JobData::Hls(response) => (
+                            "hls",
+                            response.node_info.clone().map(Into::into),
+                            new_results!(response),
+                        ),
+                    };
+                let node = node_info.as_ref();
+
+                for result in results {
+                    let icmp = result.icmp;
+                    insert.execute(params![
+                        run_id,
+                        response.received_at,
+                        response.region.name(),
+                        kind,
+                        result.endpoint,
+                        result.payload_size,
+                        result.duration,
+                        node.map(|n| &n.country_code),
+                        node.map(|n| &n.continent_code),
+                        node.map(|n| &n.region_name),
+                        node.map(|n| &n.city),
+                        node.map(|n| &n.isp),
+                        node.map(|n| n.network_type()),
+                        node.map(|n| &n.operating_system),
+                        node.map(|n| n.lat),
+                        node.map(|n| n.lon),
+                        icmp.map(|r| &r.ip_address),
+                        icmp.map(|r| r.packets_sent),
+                        icmp.map(|r| r.packets_recv),
+                        icmp.map(|r| r.packet_loss),
+                        icmp.map(|r| r.min),
+                        icmp.map(|r| r.avg),
+                        icmp.map(|r| r.max),
+                        icmp.map(|r| r.std_dev),
+                        result.error,
+                        result.result,
+                    ])?;
+                }
+            }
+        }
+
+        tx.commit()?;
+        Ok(run_id)
+    }
+
+    /// The most recent runs, newest first.
+    pub fn runs(&self, limit: usize) -> Result<Vec<RunSummary>> {
+        let mut query = self.conn.prepare(&format!(
+            "SELECT runs.id, started_at, finished_at, runs.kind, targets, regions, {}
+             FROM runs LEFT JOIN results ON results.run_id = runs.id
+             GROUP BY runs.id ORDER BY runs.id DESC LIMIT ?1",
+            aggregate_columns()
+        ))?;
+
+        let runs = query
+            .query_map(params![limit], RunSummary::from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+
+        Ok(runs)
+    }
+
+    /// A single run, if one was saved with this ID.
+    pub fn run(&self, id: i64) -> Result<Option<RunSummary>> {
+        let run = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT runs.id, started_at, finished_at, runs.kind, targets, regions, {}
+                     FROM runs LEFT JOIN results ON results.run_id = runs.id
+                     WHERE runs.id = ?1 GROUP BY runs.id",
+                    aggregate_columns()
+                ),
+                params![id],
+                RunSummary::from_row,
+            )
+            .optional()?;
+
+        Ok(run)
+    }
+
+    /// Every node result of a run, in the order they were received.
+    pub fn results(&self, run_id: i64) -> Result<Vec<StoredResult>> {
+        let mut query = self.conn.prepare(
+            "SELECT region, endpoint, payload_size, country_code, city, isp, network_type,
+                    ip_address, packet_loss, rtt_min, rtt_avg, rtt_max, duration, error
+             FROM results WHERE run_id = ?1 ORDER BY id",
+        )?;
+
+        let results = query
+            .query_map(params![run_id], |row| {
+                Ok(StoredResult {
+                    region: row.get(0)?,
+                    endpoint: row.get(1)?,
+                    payload_size: row.get(2)?,
+                    country_code: row.get(3)?,
+                    city: row.get(4)?,
+                    isp: row.get(5)?,
+                    network_type: row.get(6)?,
+                    ip_address: row.get(7)?,
+                    packet_loss: row.get(8)?,
+                    rtt_min: row.get(9)?,
+                    rtt_avg: row.get(10)?,
+                    rtt_max: row.get(11)?,
+                    duration: row.get(12)?,
+                    error: row.get(13)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        Ok(results)
+    }
+
+    /// How an endpoint was pinged across the most recent runs, only counting
+    /// nodes located in the region. Oldest first.
+    pub fn trend(
+        &self,
+        endpoint: &str,
+        region: &EarthRegion,
+        limit: usize,
+    ) -> Result<Vec<RunSummary>> {
+        let (country, continent) = region.codes();
+        let mut query = self.conn.prepare(&format!(
+            "SELECT runs.id, started_at, finished_at, runs.kind, targets, regions, {}
+             FROM runs JOIN results ON results.run_id = runs.id
+             WHERE results.kind = 'icmp' AND endpoint = ?1
+               AND (?2 IS NULL OR country_code = ?2)
+               AND (?3 IS NULL OR continent_code = ?3)
+             GROUP BY runs.id ORDER BY runs.id DESC LIMIT ?4",
+            aggregate_columns()
+        ))?;
+
+        let mut runs: Vec<RunSummary> = query
+            .query_map(
+                params![endpoint, country, continent, limit],
+                RunSummary::from_row,
+            )?
+            .collect::<rusqlite::Result<_>>()?;
+        runs.reverse();
+
+        Ok(runs)
+    }
+}
+
+/// Saves a finished run to the history database. A run that can't be saved
+/// has still been shown, so this only warns rather than failing the run.
+pub fn record_run(config: &Opts, run: &RunRecord) {
+    if config.no_history || config.watch().is_some() || run.responses.is_empty() {
+        return;
+    }
+
+    match HistoryStore::open().and_then(|mut store| store.save_run(config, run)) {
+        Ok(id) => tracing::info!(id, "Saved run to history"),
+        Err(e) => tracing::warn!(?e, "Failed to save run to history"),
+    }
+}
+
+/// The result count, reachable count, RTT min/avg/max and packet loss of the
+/// results joined against a run.
+fn aggregate_columns() -> String {
+    format!(
+        "COUNT(results.id),
+         COALESCE(SUM(CASE WHEN {REACHABLE} THEN 1 ELSE 0 END), 0),
+         MIN(CASE WHEN {REACHABLE} THEN rtt_min END),
+         AVG(CASE WHEN {REACHABLE} THEN rtt_avg END),
+         MAX(CASE WHEN {REACHABLE} THEN rtt_max END),
+         1.0 - SUM(packets_recv) / SUM(packets_sent)"
+    )
+}
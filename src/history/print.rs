@@ -0,0 +1,184 @@
+use color_eyre::eyre::{eyre, Result};
+use colorful::{Color, Colorful};
+
+use crate::{
+    display::{truncate, Printer},
+    options::{EarthRegion, HistoryCommand},
+};
+
+use super::{HistoryStore, RunSummary, StoredResult};
+
+/// Runs a `bping history` subcommand against the history database.
+pub fn run_command(command: &HistoryCommand, plain: bool) -> Result<()> {
+    let store = HistoryStore::open()?;
+    let pb = Printer::stdout(plain);
+
+    match command {
+        HistoryCommand::List { limit } => list(&pb, &store, *limit),
+        HistoryCommand::Show { run } => show(&pb, &store, *run),
+        HistoryCommand::Trend {
+            endpoint,
+            region,
+            limit,
+        } => trend(&pb, &store, endpoint, region, *limit),
+    }
+}
+
+fn list(pb: &Printer, store: &HistoryStore, limit: usize) -> Result<()> {
+    let runs = store.runs(limit)?;
+    if runs.is_empty() {
+        pb.println("No runs have been saved yet.");
+        return Ok(());
+    }
+
+    pb.println(format!(
+        "{:>5}  {:<19}  {:<4}  {:<30}  {:<20}  {:>9}  {:>10}  {:>6}",
+        "Run", "Started (UTC)", "Type", "Targets", "Regions", "Reachable", "Avg RTT", "Loss"
+    ));
+    for run in &runs {
+        pb.println(format!(
+            "{:>5}  {:<19}  {:<4}  {:<30}  {:<20}  {:>9}  {:>10}  {:>6}",
+            run.id,
+            run.started_at.format("%Y-%m-%d %H:%M:%S"),
+            run.kind,
+            truncate(&run.targets, 30),
+            truncate(&run.regions, 20),
+            format!("{}/{}", run.reachable, run.results),
+            format_ms(run.rtt_avg),
+            format_loss(run.packet_loss),
+        ));
+    }
+
+    Ok(())
+}
+
+fn show(pb: &Printer, store: &HistoryStore, id: i64) -> Result<()> {
+    let run = store
+        .run(id)?
+        .ok_or_else(|| eyre!("No run with ID {id} has been saved"))?;
+
+    pb.println(format!("Run {} · {} {}", run.id, run.kind, run.targets));
+    pb.println(format!(
+        "Started {} UTC, took {:.1}s, sent to {}",
+        run.started_at.format("%Y-%m-%d %H:%M:%S"),
+        (run.finished_at - run.started_at).num_milliseconds() as f64 / 1000.0,
+        run.regions
+    ));
+    pb.println("");
+
+    let results = store.results(id)?;
+    pb.println(format!(
+        "{:<10}  {:<24}  {:>5}  {:<2}  {:<16}  {:<20}  {:<11}  {:<15}  {:>6}  {:>26}",
+        "Region", "Endpoint", "Size", "", "City", "ISP", "Network", "IP", "Loss", "RTT min/avg/max"
+    ));
+    for result in &results {
+        pb.println(format_result(result));
+    }
+    pb.println("");
+    pb.println(format_summary(&run));
+
+    Ok(())
+}
+
+fn trend(
+    pb: &Printer,
+    store: &HistoryStore,
+    endpoint: &str,
+    region: &EarthRegion,
+    limit: usize,
+) -> Result<()> {
+    let runs = store.trend(endpoint, region, limit)?;
+    if runs.is_empty() {
+        pb.println(format!(
+            "No pings to {endpoint} from {} have been saved yet.",
+            region.name()
+        ));
+        return Ok(());
+    }
+
+    pb.println(format!("{endpoint} from {}", region.name()));
+    pb.println("");
+    pb.println(format!(
+        "{:>5}  {:<19}  {:>9}  {:>10}  {:>10}  {:>10}  {:>6}",
+        "Run", "Started (UTC)", "Reachable", "Min", "Avg", "Max", "Loss"
+    ));
+
+    let slowest = runs.iter().filter_map(|r| r.rtt_avg).fold(0.0, f64::max);
+    for run in &runs {
+        let bar = match run.rtt_avg {
+            Some(avg) if slowest > 0.0 => "█".repeat((avg / slowest * 20.0).ceil() as usize),
+            _ => String::new(),
+        };
+
+        pb.println(format!(
+            "{:>5}  {:<19}  {:>9}  {:>10}  {:>10}  {:>10}  {:>6}  {}",
+            run.id,
+            run.started_at.format("%Y-%m-%d %H:%M:%S"),
+            format!("{}/{}", run.reachable, run.results),
+            format_ms(run.rtt_min),
+            format_ms(run.rtt_avg),
+            format_ms(run.rtt_max),
+            format_loss(run.packet_loss),
+            bar.color(Color::Cyan)
+        ));
+    }
+
+    Ok(())
+}
+
+fn format_result(result: &StoredResult) -> String {
+    let line = format!(
+        "{:<10}  {:<24}  {:>5}  {:<2}  {:<16}  {:<20}  {:<11}  {:<15}",
+        truncate(&result.region, 10),
+        truncate(&result.endpoint, 24),
+        result
+            .payload_size
+            .map(|s| s.to_string())
+            .unwrap_or_default(),
+        result.country_code.as_deref().unwrap_or("??"),
+        truncate(result.city.as_deref().unwrap_or(""), 16),
+        truncate(result.isp.as_deref().unwrap_or(""), 20),
+        result.network_type.as_deref().unwrap_or(""),
+        result.ip_address.as_deref().unwrap_or(""),
+    );
+
+    if let Some(error) = &result.error {
+        return format!("{line}  {}", format!("error: {error}").color(Color::Red));
+    }
+
+    let stats = match (result.rtt_min, result.rtt_avg, result.rtt_max) {
+        (Some(min), Some(avg), Some(max)) => format!(
+            "{:>6}  {:>26}",
+            format_loss(result.packet_loss),
+            format!("{min:.2}/{avg:.2}/{max:.2} ms")
+        ),
+        _ => format!("{:>6}  {:>26}", "", format_ms(result.duration)),
+    };
+
+    if result.packet_loss.is_some_and(|loss| loss >= 1.0) {
+        format!("{line}  {}", stats.color(Color::Red))
+    } else {
+        format!("{line}  {stats}")
+    }
+}
+
+fn format_summary(run: &RunSummary) -> String {
+    let mut summary = format!("{}/{} results reachable", run.reachable, run.results);
+    if let (Some(min), Some(avg), Some(max)) = (run.rtt_min, run.rtt_avg, run.rtt_max) {
+        summary.push_str(&format!(
+            ", rtt min/avg/max = {min:.2}/{avg:.2}/{max:.2} ms"
+        ));
+    }
+    if let Some(loss) = run.packet_loss {
+        summary.push_str(&format!(", {:.1}% packet loss", loss * 100.0));
+    }
+    summary
+}
+
+fn format_ms(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |v| format!("{v:.2} ms"))
+}
+
+fn format_loss(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |l| format!("{:.1}%", l * 100.0))
+}
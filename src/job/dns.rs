@@ -0,0 +1,62 @@
+use crate::{
+    models::{errors::Errors, *},
+    options::{DnsOpts, EarthRegion, Opts},
+};
+use color_eyre::eyre::Result;
+use tracing::{debug, info};
+use types::{PerformDnsBody, PerformDnsBodyConfiguration, PerformDnsResponse};
+
+use super::execute_with_retry;
+
+#[derive(Debug)]
+pub struct DnsJob {
+    config: &'static Opts,
+    opts: &'static DnsOpts,
+    region: EarthRegion,
+}
+
+impl DnsJob {
+    pub fn new(config: &'static Opts, opts: &'static DnsOpts, region: EarthRegion) -> Self {
+        Self {
+            config,
+            opts,
+            region,
+        }
+    }
+
+    pub fn region(&self) -> &EarthRegion {
+        &self.region
+    }
+
+    pub async fn execute(
+        &self,
+        client: &Client,
+    ) -> Result<ResponseValue<PerformDnsResponse>, Errors> {
+        info!(
+            region = ?self.region,
+            hostname = self.opts.hostname,
+            "Executing DNS job"
+        );
+        let (country_code, continent_code) = self.region.get_codes()?;
+
+        let request = PerformDnsBody {
+            configuration: Some(PerformDnsBodyConfiguration {
+                dns_servers: self.opts.dns_servers.clone(),
+                lookup_types: self.opts.lookup_types.clone(),
+            }),
+            country_code,
+            continent_code,
+            hostnames: vec![self.opts.hostname.to_string()],
+            isp_regex: self.config.isp_regex.clone(),
+            city: self.config.city.clone(),
+            mobile: self.config.mobile.to_string().parse()?,
+            node_id: self.config.node_id(),
+            proxy: self.config.proxy.to_string().parse()?,
+            residential: self.config.residential.to_string().parse()?,
+        };
+
+        debug!(?request, "Sending DNS Request");
+
+        execute_with_retry(|| client.perform_dns(&request)).await
+    }
+}
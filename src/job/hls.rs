@@ -0,0 +1,61 @@
+use crate::{
+    models::{errors::Errors, *},
+    options::{EarthRegion, HlsOpts, Opts},
+};
+use color_eyre::eyre::Result;
+use tracing::{debug, info};
+use types::{PerformHlsBody, PerformHlsBodyConfiguration, PerformHlsResponse};
+
+use super::execute_with_retry;
+
+#[derive(Debug)]
+pub struct HlsJob {
+    config: &'static Opts,
+    opts: &'static HlsOpts,
+    region: EarthRegion,
+}
+
+impl HlsJob {
+    pub fn new(config: &'static Opts, opts: &'static HlsOpts, region: EarthRegion) -> Self {
+        Self {
+            config,
+            opts,
+            region,
+        }
+    }
+
+    pub fn region(&self) -> &EarthRegion {
+        &self.region
+    }
+
+    pub async fn execute(
+        &self,
+        client: &Client,
+    ) -> Result<ResponseValue<PerformHlsResponse>, Errors> {
+        info!(
+            region = ?self.region,
+            url = self.opts.url,
+            "Executing HLS job"
+        );
+        let (country_code, continent_code) = self.region.get_codes()?;
+
+        let request = PerformHlsBody {
+            configuration: Some(PerformHlsBodyConfiguration {
+                headers: self.opts.headers.clone(),
+            }),
+            country_code,
+            continent_code,
+            hostnames: vec![self.opts.url.to_string()],
+            isp_regex: self.config.isp_regex.clone(),
+            city: self.config.city.clone(),
+            mobile: self.config.mobile.to_string().parse()?,
+            node_id: self.config.node_id(),
+            proxy: self.config.proxy.to_string().parse()?,
+            residential: self.config.residential.to_string().parse()?,
+        };
+
+        debug!(?request, "Sending HLS Request");
+
+        execute_with_retry(|| client.perform_hls(&request)).await
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{
+    models::{errors::Errors, *},
+    options::{EarthRegion, HttpOpts, Opts},
+};
+use color_eyre::eyre::Result;
+use tracing::{debug, info};
+use types::{PerformHttpBody, PerformHttpBodyConfiguration, PerformHttpResponse};
+
+use super::execute_with_retry;
+
+#[derive(Debug)]
+pub struct HttpJob {
+    config: &'static Opts,
+    opts: &'static HttpOpts,
+    region: EarthRegion,
+}
+
+impl HttpJob {
+    pub fn new(config: &'static Opts, opts: &'static HttpOpts, region: EarthRegion) -> Self {
+        Self {
+            config,
+            opts,
+            region,
+        }
+    }
+
+    pub fn region(&self) -> &EarthRegion {
+        &self.region
+    }
+
+    pub async fn execute(
+        &self,
+        client: &Client,
+    ) -> Result<ResponseValue<PerformHttpResponse>, Errors> {
+        info!(
+            region = ?self.region,
+            method = self.opts.method,
+            url = self.opts.url,
+            "Executing HTTP job"
+        );
+        let (country_code, continent_code) = self.region.get_codes()?;
+
+        let request = PerformHttpBody {
+            configuration: Some(PerformHttpBodyConfiguration {
+                body: self.opts.body.clone(),
+                headers: self.opts.headers.clone(),
+                regex: self.opts.regex.clone(),
+                return_body: Some(self.opts.return_body),
+                status_codes: self.opts.status_codes.iter().map(|&c| c as f64).collect(),
+            }),
+            country_code,
+            continent_code,
+            hostnames: vec![self.opts.url.to_string()],
+            isp_regex: self.config.isp_regex.clone(),
+            city: self.config.city.clone(),
+            mobile: self.config.mobile.to_string().parse()?,
+            node_id: self.config.node_id(),
+            proxy: self.config.proxy.to_string().parse()?,
+            residential: self.config.residential.to_string().parse()?,
+        };
+
+        debug!(?request, "Sending HTTP Request");
+
+        execute_with_retry(|| client.perform_http(&self.opts.method, &request)).await
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{
+    models::{errors::Errors, *},
+    options::{EarthRegion, IcmpOpts, Opts},
+};
+use color_eyre::eyre::Result;
+use tracing::{debug, info};
+use types::{PerformIcmpBody, PerformIcmpBodyConfiguration, PerformIcmpResponse};
+
+use super::execute_with_retry;
+
+#[derive(Debug)]
+pub struct IcmpJob {
+    config: &'static Opts,
+    opts: &'static IcmpOpts,
+    region: EarthRegion,
+    payload_size: usize,
+}
+
+impl IcmpJob {
+    pub fn new(
+        config: &'static Opts,
+        opts: &'static IcmpOpts,
+        region: EarthRegion,
+        payload_size: usize,
+    ) -> Self {
+        Self {
+            config,
+            opts,
+            region,
+            payload_size,
+        }
+    }
+
+    pub fn region(&self) -> &EarthRegion {
+        &self.region
+    }
+
+    pub fn payload_size(&self) -> usize {
+        self.payload_size
+    }
+
+    pub async fn execute(
+        &self,
+        client: &Client,
+    ) -> Result<ResponseValue<PerformIcmpResponse>, Errors> {
+        info!(
+            region = ?self.region,
+            attempts = self.opts.count,
+            payload_size = self.payload_size,
+            endpoints = ?self.opts.endpoints,
+            "Executing ICMP job"
+        );
+        let (country_code, continent_code) = self.region.get_codes()?;
+
+        let request = PerformIcmpBody {
+            configuration: Some(PerformIcmpBodyConfiguration {
+                payload_size: Some(self.payload_size as f64),
+                timeout_millis: self.opts.timeout.map(|t| t as f64),
+                attempts: Some(self.opts.count as f64),
+            }),
+            country_code,
+            continent_code,
+            hostnames: self.opts.endpoints.clone(),
+            isp_regex: self.config.isp_regex.clone(),
+            city: self.config.city.clone(),
+            mobile: self.config.mobile.to_string().parse()?,
+            node_id: self.config.node_id(),
+            proxy: self.config.proxy.to_string().parse()?,
+            residential: self.config.residential.to_string().parse()?,
+        };
+
+        debug!(?request, "Sending ICMP Request");
+
+        execute_with_retry(|| client.perform_icmp(&request)).await
+    }
+}
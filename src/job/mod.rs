@@ -0,0 +1,233 @@
+use crate::{
+    display::ProgressUpdater,
+    models::{errors::Errors, *},
+    options::{EarthRegion, JobKind, Opts},
+};
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::{Context, Result};
+use futures::stream::{self, StreamExt};
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde::Serialize;
+use std::{future::Future, iter::repeat_n, sync::Arc, time::Duration};
+use tokio_retry::{strategy::ExponentialBackoff, Retry};
+use tracing::{error, info};
+use types::{PerformDnsResponse, PerformHlsResponse, PerformHttpResponse, PerformIcmpResponse};
+
+mod dns;
+mod hls;
+mod http;
+mod icmp;
+
+pub use dns::DnsJob;
+pub use hls::HlsJob;
+pub use http::HttpJob;
+pub use icmp::IcmpJob;
+
+/// A single request to the Bitping API, sent to one region.
+#[derive(Debug)]
+pub enum Job {
+    Icmp(IcmpJob),
+    Dns(DnsJob),
+    Http(HttpJob),
+    Hls(HlsJob),
+}
+
+/// The response of a [`Job`] along with the region it was sent to, handed to
+/// the display once it lands.
+#[derive(Debug, Serialize)]
+pub struct JobResponse {
+    pub region: EarthRegion,
+    pub received_at: DateTime<Utc>,
+    pub data: JobData,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum JobData {
+    Icmp {
+        payload_size: usize,
+        #[serde(flatten)]
+        response: PerformIcmpResponse,
+    },
+    Dns(PerformDnsResponse),
+    Http(PerformHttpResponse),
+    Hls(PerformHlsResponse),
+}
+
+impl Job {
+    /// Builds the jobs to send to a region. This is a single job, except for
+    /// ICMP payload size sweeps which send one job per payload size.
+    pub fn for_region(config: &'static Opts, region: &EarthRegion) -> Vec<Self> {
+        match &config.job {
+            JobKind::Icmp(opts) => opts
+                .payload_sizes()
+                .into_iter()
+                .map(|size| Job::Icmp(IcmpJob::new(config, opts, region.clone(), size)))
+                .collect(),
+            JobKind::Dns(opts) => vec![Job::Dns(DnsJob::new(config, opts, region.clone()))],
+            JobKind::Http(opts) => vec![Job::Http(HttpJob::new(config, opts, region.clone()))],
+            JobKind::Hls(opts) => vec![Job::Hls(HlsJob::new(config, opts, region.clone()))],
+        }
+    }
+
+    pub fn region(&self) -> &EarthRegion {
+        match self {
+            Job::Icmp(job) => job.region(),
+            Job::Dns(job) => job.region(),
+            Job::Http(job) => job.region(),
+            Job::Hls(job) => job.region(),
+        }
+    }
+
+    pub async fn execute(&self, client: &Client) -> Result<JobResponse, Errors> {
+        let data = match self {
+            Job::Icmp(job) => JobData::Icmp {
+                payload_size: job.payload_size(),
+                response: job.execute(client).await?.into_inner(),
+            },
+            Job::Dns(job) => JobData::Dns(job.execute(client).await?.into_inner()),
+            Job::Http(job) => JobData::Http(job.execute(client).await?.into_inner()),
+            Job::Hls(job) => JobData::Hls(job.execute(client).await?.into_inner()),
+        };
+
+        Ok(JobResponse {
+            region: self.region().clone(),
+            received_at: Utc::now(),
+            data,
+        })
+    }
+}
+
+async fn execute_with_retry<T, F, Fut>(mut action: F) -> Result<ResponseValue<T>, Errors>
+where
+    Errors: From<Error<T>>,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<ResponseValue<T>, Error<T>>>,
+{
+    let retry_strategy = ExponentialBackoff::from_millis(100)
+        .factor(2)
+        .max_delay(Duration::from_millis(200))
+        .take(3);
+
+    info!("Executing request with retry strategy");
+    Retry::start(retry_strategy, || {
+        let request = action();
+        async move { request.await.map_err(Errors::from) }
+    })
+    .await
+}
+
+pub struct JobScheduler {
+    config: &'static Opts,
+    client: Client,
+}
+
+impl JobScheduler {
+    pub fn new(config: &'static Opts) -> Result<Self> {
+        info!(
+            concurrency = config.concurrency,
+            attempts = config.attempts,
+            "Initializing job scheduler"
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-api-key",
+            HeaderValue::try_from(&config.api_key)
+                .context("Unable to parse API Key into header")?,
+        );
+
+        let req_client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .context("Failed to build HTTP client")?;
+        let client = Client::new_with_client("https://api.bitping.com/v2", req_client);
+
+        Ok(Self { config, client })
+    }
+
+    pub async fn execute_jobs(&self, mut progress: ProgressUpdater) -> Result<(), Error> {
+        info!(
+            regions = ?self.config.regions,
+            "Starting job execution"
+        );
+        let reruns = progress.take_reruns();
+        let progress = Arc::new(progress);
+
+        let Some(watch) = self.config.watch() else {
+            self.execute_cycle(&progress).await;
+
+            // The dashboard keeps the run open until it's closed, sending
+            // back the regions to run again.
+            if let Some(mut reruns) = reruns {
+                while let Some(region) = reruns.recv().await {
+                    info!(?region, "Running region again");
+                    self.execute(&progress, self.region_jobs(&region)).await;
+                }
+            }
+            return Ok(());
+        };
+
+        let mut cycle = 0;
+        loop {
+            self.execute_cycle(&progress).await;
+            progress.cycle_finished().await;
+
+            cycle += 1;
+            if watch.cycles.is_some_and(|cycles| cycle >= cycles) {
+                break;
+            }
+            tokio::time::sleep(watch.interval).await;
+        }
+
+        Ok(())
+    }
+
+    async fn execute_cycle(&self, progress: &Arc<ProgressUpdater>) {
+        self.execute(progress, self.jobs_iterator()).await;
+    }
+
+    async fn execute(&self, progress: &Arc<ProgressUpdater>, jobs: impl Iterator<Item = Job>) {
+        stream::iter(jobs)
+            .for_each_concurrent(Some(self.config.concurrency), |job| {
+                let client = self.client.clone();
+
+                let progress = progress.clone();
+                async move {
+                    if progress.is_closed() {
+                        return;
+                    }
+
+                    match job.execute(&client).await {
+                        Ok(v) => progress.display_job(v).await,
+                        Err(Errors::UnauthorizedError) => {
+                            error!("{}", Errors::UnauthorizedError);
+                            progress
+                                .job_failed(job.region(), Errors::UnauthorizedError.to_string())
+                                .await;
+                        }
+                        Err(e) => {
+                            error!(?e, "Job failed");
+                            progress.job_failed(job.region(), e.to_string()).await;
+                        }
+                    };
+                }
+            })
+            .await;
+    }
+
+    /// Every job of the run, region by region, with each region repeated for
+    /// every attempt.
+    fn jobs_iterator(&self) -> impl Iterator<Item = Job> + '_ {
+        self.config
+            .regions
+            .iter()
+            .flat_map(move |region| self.region_jobs(region))
+    }
+
+    /// Every attempt at the jobs for a single region.
+    fn region_jobs<'a>(&self, region: &'a EarthRegion) -> impl Iterator<Item = Job> + 'a {
+        let config = self.config;
+        repeat_n(region, config.attempts).flat_map(move |r| Job::for_region(config, r))
+    }
+}
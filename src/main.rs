@@ -1,40 +1,63 @@
 use std::sync::LazyLock;
 
-use color_eyre::eyre::{self, Context, ContextCompat};
+use color_eyre::eyre;
 use display::ProgressDisplay;
-use job::{IcmpJob, JobScheduler};
-use options::Opts;
+use job::JobScheduler;
+use options::Command;
 use tokio::join;
-use tracing::{error, info};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use tracing::info;
+use tracing_subscriber::{
+    fmt::writer::BoxMakeWriter, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter,
+};
 
 mod display;
+mod history;
 mod job;
 mod models;
 mod options;
+mod output;
+mod stats;
 
-static APP_CONFIG: LazyLock<Opts> = LazyLock::new(|| Opts::parser().run());
+static APP_COMMAND: LazyLock<Command> = LazyLock::new(|| Command::parser().run());
 
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
     color_eyre::install()?;
     let filter = EnvFilter::from_default_env();
 
+    // Logs would draw over the dashboard, which shows job errors itself.
+    let writer = match &*APP_COMMAND {
+        Command::Run(config) if config.tui => BoxMakeWriter::new(std::io::sink),
+        _ => BoxMakeWriter::new(std::io::stderr),
+    };
+
+    let colour = match &*APP_COMMAND {
+        Command::Run(config) => display::wants_colour(config.plain),
+        Command::History { plain, .. } => display::wants_colour(*plain),
+    };
+
     let fmt = tracing_subscriber::fmt::Layer::default()
         .compact()
         .pretty()
         .with_thread_ids(true)
-        .with_target(false);
+        .with_target(false)
+        .with_ansi(colour)
+        .with_writer(writer);
     tracing_subscriber::registry().with(fmt).with(filter).init();
 
-    let (mut progress, updater) = ProgressDisplay::new(&APP_CONFIG)?;
-    let scheduler = JobScheduler::new(&APP_CONFIG)?;
+    let config = match &*APP_COMMAND {
+        Command::Run(config) => config.as_ref(),
+        Command::History { command, plain } => return history::run_command(command, *plain),
+    };
+
+    let (mut progress, updater) = ProgressDisplay::new(config)?;
+    let scheduler = JobScheduler::new(config)?;
 
-    info!(config = ?&APP_CONFIG, "Starting job execution with config");
+    info!(?config, "Starting job execution with config");
 
     let display_driver = progress.display_job_thread();
     let schedule_driver = scheduler.execute_jobs(updater);
-    let _ = join!(schedule_driver, display_driver);
+    let (_, display) = join!(schedule_driver, display_driver);
 
-    Ok(())
+    display
 }
@@ -24,7 +24,6 @@ impl BpingDiagnosticsConfig {
 
 impl Default for BpingDiagnosticsConfig {
   fn default() -> Self { 
-    let diag_config = BpingDiagnosticsConfig::new(false, 3);
-    return diag_config;
+    BpingDiagnosticsConfig::new(false, 3)
   }
 }
\ No newline at end of file
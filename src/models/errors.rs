@@ -2,7 +2,10 @@ use progenitor::progenitor_client;
 use reqwest::StatusCode;
 use thiserror::Error;
 
-use super::types::{error::ConversionError, PerformIcmpResponse};
+use super::types::{
+    error::ConversionError, PerformDnsResponse, PerformHlsResponse, PerformHttpResponse,
+    PerformIcmpResponse,
+};
 
 #[derive(Error, Debug)]
 pub enum Errors {
@@ -11,18 +14,39 @@ pub enum Errors {
     #[error("Some configuration was passed to the request that is not allowed {0}")]
     ImpossibleRequestConfiguration(#[from] ConversionError),
     #[error(transparent)]
-    ProgenitorError(progenitor_client::Error<PerformIcmpResponse>),
+    IcmpRequestError(Box<progenitor_client::Error<PerformIcmpResponse>>),
+    #[error(transparent)]
+    DnsRequestError(Box<progenitor_client::Error<PerformDnsResponse>>),
+    #[error(transparent)]
+    HttpRequestError(Box<progenitor_client::Error<PerformHttpResponse>>),
+    #[error(transparent)]
+    HlsRequestError(Box<progenitor_client::Error<PerformHlsResponse>>),
 }
 
-impl From<progenitor_client::Error<PerformIcmpResponse>> for Errors {
-    fn from(value: progenitor_client::Error<PerformIcmpResponse>) -> Self {
-        match value {
-            progenitor_client::Error::UnexpectedResponse(response)
-                if response.status() == StatusCode::UNAUTHORIZED =>
-            {
-                Errors::UnauthorizedError
+/// Each job endpoint has its own error type, but an unauthorized response
+/// means the same thing for all of them.
+macro_rules! impl_from_request_error {
+    ($($variant:ident => $ty:ty),*) => {
+        $(
+            impl From<progenitor_client::Error<$ty>> for Errors {
+                fn from(value: progenitor_client::Error<$ty>) -> Self {
+                    match value {
+                        progenitor_client::Error::UnexpectedResponse(response)
+                            if response.status() == StatusCode::UNAUTHORIZED =>
+                        {
+                            Errors::UnauthorizedError
+                        }
+                        e => Errors::$variant(Box::new(e)),
+                    }
+                }
             }
-            e => Errors::ProgenitorError(e),
-        }
-    }
+        )*
+    };
 }
+
+impl_from_request_error!(
+    IcmpRequestError => PerformIcmpResponse,
+    DnsRequestError => PerformDnsResponse,
+    HttpRequestError => PerformHttpResponse,
+    HlsRequestError => PerformHlsResponse
+);
@@ -0,0 +1,174 @@
+use super::types::*;
+
+/// progenitor generates a separate type for every nesting level of the HLS
+/// response even though the master playlist, renditions and fragments share the
+/// same metric shapes. These flattened types give the display one shape to walk.
+#[derive(Debug, Clone)]
+pub struct HlsStream {
+    pub master: Option<HlsPlaylist>,
+    pub renditions: Vec<HlsRendition>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HlsPlaylist {
+    pub file: String,
+    pub download: Option<HlsDownload>,
+    pub timings: Option<HlsTimings>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HlsRendition {
+    pub playlist: HlsPlaylist,
+    pub resolution: String,
+    pub bandwidth: f64,
+    pub target_duration_secs: f64,
+    pub fragments: Vec<HlsFragment>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HlsFragment {
+    pub playlist: HlsPlaylist,
+    pub duration_secs: f64,
+    pub download_ratio: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct HlsDownload {
+    pub size: f64,
+    pub time_ms: f64,
+    pub bytes_per_second: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct HlsTimings {
+    pub dns_resolve_ms: Option<f64>,
+    pub tcp_connect_ms: f64,
+    pub tls_handshake_ms: Option<f64>,
+    pub http_send_ms: f64,
+    pub http_ttfb_ms: f64,
+}
+
+impl From<PerformHlsResponseResultsItemResult> for HlsStream {
+    fn from(value: PerformHlsResponseResultsItemResult) -> Self {
+        match (value.master, value.rendition) {
+            (Some(master), _) => Self {
+                master: Some(HlsPlaylist {
+                    file: master.file,
+                    download: master.download_metrics.map(Into::into),
+                    timings: master.metrics.map(Into::into),
+                }),
+                renditions: master.renditions.into_iter().map(Into::into).collect(),
+            },
+            (None, rendition) => Self {
+                master: None,
+                renditions: rendition.into_iter().map(Into::into).collect(),
+            },
+        }
+    }
+}
+
+macro_rules! impl_from_download {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for HlsDownload {
+                fn from(value: $ty) -> Self {
+                    Self {
+                        size: value.size,
+                        time_ms: value.time_ms,
+                        bytes_per_second: value.bytes_per_second,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_timings {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for HlsTimings {
+                fn from(value: $ty) -> Self {
+                    Self {
+                        dns_resolve_ms: value.dns_resolve_duration_ms,
+                        tcp_connect_ms: value.tcp_connect_duration_ms,
+                        tls_handshake_ms: value.tls_handshake_duration_ms,
+                        http_send_ms: value.http_get_send_duration_ms,
+                        http_ttfb_ms: value.http_ttfb_duration_ms,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_fragment {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for HlsFragment {
+                fn from(value: $ty) -> Self {
+                    Self {
+                        playlist: HlsPlaylist {
+                            file: value.file,
+                            download: value.download_metrics.map(Into::into),
+                            timings: value.metrics.map(Into::into),
+                        },
+                        duration_secs: value.content_fragment_duration_secs,
+                        download_ratio: value.download_ratio,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_rendition {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for HlsRendition {
+                fn from(value: $ty) -> Self {
+                    Self {
+                        playlist: HlsPlaylist {
+                            file: value.file,
+                            download: value.download_metrics.map(Into::into),
+                            timings: value.metrics.map(Into::into),
+                        },
+                        resolution: value.resolution,
+                        bandwidth: value.bandwidth,
+                        target_duration_secs: value.target_duration_secs,
+                        fragments: value
+                            .content_fragment_metrics
+                            .into_iter()
+                            .map(Into::into)
+                            .collect(),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_download!(
+    PerformHlsResponseResultsItemResultMasterDownloadMetrics,
+    PerformHlsResponseResultsItemResultMasterRenditionsItemDownloadMetrics,
+    PerformHlsResponseResultsItemResultMasterRenditionsItemContentFragmentMetricsItemDownloadMetrics,
+    PerformHlsResponseResultsItemResultRenditionDownloadMetrics,
+    PerformHlsResponseResultsItemResultRenditionContentFragmentMetricsItemDownloadMetrics
+);
+
+impl_from_timings!(
+    PerformHlsResponseResultsItemResultMasterMetrics,
+    PerformHlsResponseResultsItemResultMasterRenditionsItemMetrics,
+    PerformHlsResponseResultsItemResultMasterRenditionsItemContentFragmentMetricsItemMetrics,
+    PerformHlsResponseResultsItemResultRenditionMetrics,
+    PerformHlsResponseResultsItemResultRenditionContentFragmentMetricsItemMetrics
+);
+
+impl_from_fragment!(
+    PerformHlsResponseResultsItemResultMasterRenditionsItemContentFragmentMetricsItem,
+    PerformHlsResponseResultsItemResultRenditionContentFragmentMetricsItem
+);
+
+impl_from_rendition!(
+    PerformHlsResponseResultsItemResultMasterRenditionsItem,
+    PerformHlsResponseResultsItemResultRendition
+);
@@ -0,0 +1,19 @@
+use super::types::PerformIcmpResponseResultsItem;
+
+impl PerformIcmpResponseResultsItem {
+    /// Packet loss from 0 to 1, unless the node returned an error instead.
+    pub fn packet_loss(&self) -> Option<f64> {
+        self.result
+            .as_ref()
+            .filter(|_| self.error.is_none())
+            .map(|r| r.packet_loss)
+    }
+
+    /// The node's average RTT, if any replies came back.
+    pub fn rtt_avg(&self) -> Option<f64> {
+        self.result
+            .as_ref()
+            .filter(|r| self.error.is_none() && r.packet_loss < 1.0)
+            .map(|r| r.avg)
+    }
+}
@@ -1,11 +1,22 @@
 mod api;
+#[allow(dead_code)]
 mod auth;
+#[allow(dead_code)]
 mod config_models;
+mod hls;
+mod icmp;
+mod node_info;
+#[allow(dead_code)]
 mod nodes;
 
 pub use api::*;
+#[allow(unused_imports)]
 pub use auth::*;
+#[allow(unused_imports)]
 pub use config_models::*;
+pub use hls::*;
+pub use node_info::*;
+#[allow(unused_imports)]
 pub use nodes::*;
 
 pub mod errors;
@@ -0,0 +1,75 @@
+use super::types::{
+    PerformDnsResponseNodeInfo, PerformHlsResponseNodeInfo, PerformHttpResponseNodeInfo,
+    PerformIcmpResponseNodeInfo,
+};
+
+/// Every job endpoint describes the node that served it with the same schema,
+/// but progenitor generates a distinct type per operation. The ICMP one is used
+/// as the canonical type so the display code only has to deal with one shape.
+pub type NodeInfo = PerformIcmpResponseNodeInfo;
+
+macro_rules! impl_from_node_info {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for NodeInfo {
+                fn from(value: $ty) -> Self {
+                    Self {
+                        city: value.city,
+                        continent_code: value.continent_code,
+                        country_code: value.country_code,
+                        isp: value.isp,
+                        lat: value.lat,
+                        lon: value.lon,
+                        mobile: value.mobile,
+                        operating_system: value.operating_system,
+                        proxy: value.proxy,
+                        region_name: value.region_name,
+                        residential: value.residential,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_node_info!(
+    PerformDnsResponseNodeInfo,
+    PerformHttpResponseNodeInfo,
+    PerformHlsResponseNodeInfo
+);
+
+impl NodeInfo {
+    /// The country the node is in, if its country code is a known one.
+    pub fn country(&self) -> Option<keshvar::Country> {
+        keshvar::Alpha2::try_from(self.country_code.as_str())
+            .ok()
+            .map(|alpha2| alpha2.to_country())
+    }
+
+    pub fn continent_name(&self) -> &str {
+        match self.continent_code.as_str() {
+            "AF" => "Africa",
+            "AN" => "Antarctica",
+            "AS" => "Asia",
+            "EU" => "Europe",
+            "NA" => "North America",
+            "OC" => "Oceania",
+            "SA" => "South America",
+            code => code,
+        }
+    }
+
+    /// The kind of network the node is on, for tagging and grouping results.
+    /// Mobile and proxy nodes are usually residential too, so they win.
+    pub fn network_type(&self) -> &'static str {
+        if self.mobile {
+            "mobile"
+        } else if self.proxy {
+            "proxy"
+        } else if self.residential {
+            "residential"
+        } else {
+            "datacenter"
+        }
+    }
+}
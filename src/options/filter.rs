@@ -0,0 +1,207 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Serialize, Serializer};
+
+/// A condition a node's results must meet to be displayed, eg. `loss>0` or
+/// `isp~telstra`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultFilter {
+    pub field: FilterField,
+    pub op: FilterOp,
+    pub value: FilterValue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterField {
+    /// Packet loss as a percentage, from 0 to 100.
+    Loss,
+    /// Average round trip time in milliseconds.
+    Rtt,
+    Min,
+    Max,
+    Country,
+    Continent,
+    City,
+    Isp,
+    Network,
+    Region,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    /// Contains, ignoring case.
+    Contains,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue {
+    Number(f64),
+    Text(String),
+}
+
+/// Operators, longest first so `<=` isn't read as `<`.
+const OPERATORS: [(&str, FilterOp); 7] = [
+    ("<=", FilterOp::Le),
+    (">=", FilterOp::Ge),
+    ("!=", FilterOp::Ne),
+    ("<", FilterOp::Lt),
+    (">", FilterOp::Gt),
+    ("=", FilterOp::Eq),
+    ("~", FilterOp::Contains),
+];
+
+impl FilterField {
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            FilterField::Loss | FilterField::Rtt | FilterField::Min | FilterField::Max
+        )
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            FilterField::Loss => "loss",
+            FilterField::Rtt => "rtt",
+            FilterField::Min => "min",
+            FilterField::Max => "max",
+            FilterField::Country => "country",
+            FilterField::Continent => "continent",
+            FilterField::City => "city",
+            FilterField::Isp => "isp",
+            FilterField::Network => "network",
+            FilterField::Region => "region",
+        }
+    }
+}
+
+impl FilterOp {
+    fn symbol(&self) -> &'static str {
+        OPERATORS
+            .iter()
+            .find(|(_, op)| op == self)
+            .map_or("", |(symbol, _)| symbol)
+    }
+}
+
+impl FromStr for FilterField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "loss" => Ok(FilterField::Loss),
+            "rtt" | "avg" => Ok(FilterField::Rtt),
+            "min" => Ok(FilterField::Min),
+            "max" => Ok(FilterField::Max),
+            "country" => Ok(FilterField::Country),
+            "continent" => Ok(FilterField::Continent),
+            "city" => Ok(FilterField::City),
+            "isp" => Ok(FilterField::Isp),
+            "network" | "network-type" => Ok(FilterField::Network),
+            "region" => Ok(FilterField::Region),
+            _ => Err(format!(
+                "Unknown filter field '{s}'. Expected loss, rtt, min, max, country, continent, city, isp, network or region"
+            )),
+        }
+    }
+}
+
+impl FromStr for ResultFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((index, symbol, op)) = OPERATORS
+            .iter()
+            .filter_map(|(symbol, op)| s.find(symbol).map(|index| (index, *symbol, *op)))
+            .min_by_key(|(index, symbol, _)| (*index, usize::MAX - symbol.len()))
+        else {
+            return Err(format!(
+                "Filter '{s}' must be in the form <field><op><value>, eg. loss>0"
+            ));
+        };
+
+        let field: FilterField = s[..index].parse()?;
+        let value = s[index + symbol.len()..].trim();
+        if value.is_empty() {
+            return Err(format!("Filter '{s}' is missing a value"));
+        }
+
+        let value = if field.is_numeric() {
+            if op == FilterOp::Contains {
+                return Err(format!("'~' can't be used with {}", field.name()));
+            }
+            let number = value
+                .trim_end_matches("ms")
+                .trim_end_matches('%')
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("{} must be compared to a number", field.name()))?;
+            FilterValue::Number(number)
+        } else {
+            if !matches!(op, FilterOp::Eq | FilterOp::Ne | FilterOp::Contains) {
+                return Err(format!(
+                    "{} can only be compared with =, != or ~",
+                    field.name()
+                ));
+            }
+            FilterValue::Text(value.to_string())
+        };
+
+        Ok(ResultFilter { field, op, value })
+    }
+}
+
+impl ResultFilter {
+    /// Whether a number meets the condition.
+    pub fn matches_number(&self, actual: f64) -> bool {
+        let FilterValue::Number(expected) = self.value else {
+            return false;
+        };
+
+        match self.op {
+            FilterOp::Lt => actual < expected,
+            FilterOp::Le => actual <= expected,
+            FilterOp::Gt => actual > expected,
+            FilterOp::Ge => actual >= expected,
+            FilterOp::Eq => actual == expected,
+            FilterOp::Ne => actual != expected,
+            FilterOp::Contains => false,
+        }
+    }
+
+    /// Whether some text meets the condition, ignoring case.
+    pub fn matches_text(&self, actual: &str) -> bool {
+        let FilterValue::Text(expected) = &self.value else {
+            return false;
+        };
+        let (actual, expected) = (actual.to_lowercase(), expected.to_lowercase());
+
+        match self.op {
+            FilterOp::Eq => actual == expected,
+            FilterOp::Ne => actual != expected,
+            FilterOp::Contains => actual.contains(&expected),
+            _ => false,
+        }
+    }
+}
+
+impl Display for ResultFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match &self.value {
+            FilterValue::Number(number) => number.to_string(),
+            FilterValue::Text(text) => text.clone(),
+        };
+        write!(f, "{}{}{}", self.field.name(), self.op.symbol(), value)
+    }
+}
+
+impl Serialize for ResultFilter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
@@ -0,0 +1,73 @@
+use bpaf::Parser;
+use color_eyre::eyre;
+
+use super::{opts::plain, parse_alpha_codes, EarthRegion};
+
+/// Queries against the runs saved to the local history database.
+#[derive(Debug, Clone)]
+pub enum HistoryCommand {
+    /// The most recent runs, newest first.
+    List { limit: usize },
+    /// Every node result of a single run.
+    Show { run: i64 },
+    /// How an endpoint performed across runs, from nodes in a region.
+    Trend {
+        endpoint: String,
+        region: EarthRegion,
+        limit: usize,
+    },
+}
+
+impl HistoryCommand {
+    /// Parses a subcommand along with whether `--plain` was given to it.
+    pub(super) fn parser() -> impl Parser<(Self, bool)> {
+        let limit = runs_limit();
+        let list = bpaf::construct!(HistoryCommand::List { limit });
+        let list = with_plain(list)
+            .to_options()
+            .descr("List the most recent runs.")
+            .command("list");
+
+        let run = bpaf::positional::<i64>("run")
+            .help("The ID of the run, as shown by 'bping history list'.");
+        let show = with_plain(bpaf::construct!(HistoryCommand::Show { run }))
+            .to_options()
+            .descr("Show every node result of a run.")
+            .command("show");
+
+        let endpoint = bpaf::positional::<String>("endpoint").help("The endpoint that was pinged.");
+        let region = bpaf::long("region")
+            .short('r')
+            .help("Only include nodes located in this country or continent. Defaults to Anywhere.")
+            .argument::<String>("region")
+            .parse(|r| match parse_alpha_codes(&r)?.as_slice() {
+                [region] => Ok(region.clone()),
+                _ => Err(eyre::eyre!("Only a single region can be given")),
+            })
+            .fallback(EarthRegion::Anywhere);
+        let limit = runs_limit();
+        let trend = with_plain(bpaf::construct!(HistoryCommand::Trend {
+            region,
+            limit,
+            endpoint
+        }))
+        .to_options()
+        .descr("Show how an endpoint performed across runs.")
+        .command("trend");
+
+        bpaf::construct!([list, show, trend])
+    }
+}
+
+fn with_plain(command: impl Parser<HistoryCommand>) -> impl Parser<(HistoryCommand, bool)> {
+    let plain = plain();
+    bpaf::construct!(command, plain)
+}
+
+fn runs_limit() -> impl Parser<usize> {
+    bpaf::long("limit")
+        .short('n')
+        .help("Specifies how many runs to show. Defaults to 20.")
+        .argument::<usize>("limit")
+        .fallback(20)
+}
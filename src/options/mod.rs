@@ -1,3 +1,11 @@
+mod filter;
+mod history;
+mod node;
 mod opts;
+mod output;
 
-pub use opts::*;
\ No newline at end of file
+pub use filter::*;
+pub use history::*;
+pub use node::*;
+pub use opts::*;
+pub use output::*;
@@ -0,0 +1,140 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Serialize, Serializer};
+
+use crate::models::NodeInfo;
+
+use super::NetworkPolicy;
+
+const NETWORK_TYPES: [&str; 4] = ["mobile", "proxy", "residential", "datacenter"];
+
+/// The node `--node` sends every job to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeSelector {
+    /// A Bitping node ID.
+    Id(String),
+    /// The identity printed next to every result, as results don't include
+    /// the ID of the node that ran them.
+    Identity(NodeIdentity),
+}
+
+/// Everything a result says about the node that ran it that jobs can also be
+/// sent by, written as `country:city:network:isp`, eg.
+/// `AU:Sydney:residential:Telstra`. The ISP goes last as it's the likeliest
+/// to contain a `:` of its own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeIdentity {
+    pub country_code: String,
+    pub city: String,
+    pub network_type: String,
+    pub isp: String,
+}
+
+impl From<&NodeInfo> for NodeIdentity {
+    fn from(node: &NodeInfo) -> Self {
+        Self {
+            country_code: node.country_code.clone(),
+            city: node.city.clone(),
+            network_type: node.network_type().to_string(),
+            isp: node.isp.clone(),
+        }
+    }
+}
+
+impl NodeIdentity {
+    /// A regular expression matching the ISP's name and nothing else.
+    pub fn isp_regex(&self) -> String {
+        let mut regex = String::from("^");
+        for c in self.isp.chars() {
+            if "\\^$.|?*+()[]{}/".contains(c) {
+                regex.push('\\');
+            }
+            regex.push(c);
+        }
+        regex.push('$');
+        regex
+    }
+
+    /// The residential, mobile and proxy policies that only allow nodes on
+    /// the same kind of network, following [`NodeInfo::network_type`] in
+    /// letting mobile win over proxy and both over residential.
+    pub fn network_policies(&self) -> (NetworkPolicy, NetworkPolicy, NetworkPolicy) {
+        use NetworkPolicy::*;
+
+        match self.network_type.as_str() {
+            "mobile" => (Allowed, Required, Allowed),
+            "proxy" => (Allowed, Denied, Required),
+            "residential" => (Required, Denied, Denied),
+            _ => (Denied, Denied, Denied),
+        }
+    }
+}
+
+impl FromStr for NodeSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("Node cannot be empty".to_string());
+        }
+        if !s.contains(':') {
+            return Ok(NodeSelector::Id(s.to_string()));
+        }
+
+        let parts: Vec<&str> = s.splitn(4, ':').map(str::trim).collect();
+        let [country_code, city, network_type, isp] = parts[..] else {
+            return Err(format!(
+                "Node '{s}' must be an ID or country:city:network:isp, eg. AU:Sydney:residential:Telstra"
+            ));
+        };
+
+        let country_code = country_code.to_uppercase();
+        if keshvar::Alpha2::try_from(country_code.as_str()).is_err() {
+            return Err(format!(
+                "Unknown country code '{country_code}' in node '{s}'"
+            ));
+        }
+        let network_type = network_type.to_lowercase();
+        if !NETWORK_TYPES.contains(&network_type.as_str()) {
+            return Err(format!(
+                "Unknown network '{network_type}' in node '{s}'. Expected mobile, proxy, residential or datacenter"
+            ));
+        }
+        if city.is_empty() || isp.is_empty() {
+            return Err(format!("Node '{s}' is missing a city or ISP"));
+        }
+
+        Ok(NodeSelector::Identity(NodeIdentity {
+            country_code,
+            city: city.to_string(),
+            network_type,
+            isp: isp.to_string(),
+        }))
+    }
+}
+
+impl Display for NodeIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}",
+            self.country_code, self.city, self.network_type, self.isp
+        )
+    }
+}
+
+impl Display for NodeSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeSelector::Id(id) => write!(f, "{id}"),
+            NodeSelector::Identity(identity) => write!(f, "{identity}"),
+        }
+    }
+}
+
+impl Serialize for NodeSelector {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
@@ -1,15 +1,21 @@
-use std::{fmt::Display, str::FromStr};
+use std::{collections::HashMap, fmt::Display, path::PathBuf, str::FromStr, time::Duration};
 
-use bpaf::{long, OptionParser, Parser};
+use bpaf::{OptionParser, Parser};
 use color_eyre::eyre::{self, Result};
 use keshvar::Continent;
+use serde::{Serialize, Serializer};
 
+use super::{
+    HistoryCommand, NodeSelector, OutputFormat, ResultFilter, ResultGrouping, ResultOrder, SummaryGrouping,
+    Thresholds,
+};
 use crate::models::{
     errors::Errors,
-    types::{PerformIcmpBodyContinentCode, PerformIcmpBodyCountryCode},
+    types::{error::ConversionError, PerformDnsBodyConfigurationLookupTypesItem},
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum NetworkPolicy {
     Allowed,
     Denied,
@@ -38,26 +44,276 @@ impl Display for NetworkPolicy {
 
 /// This doc string acts as a help message when the user runs '--help'
 /// as do all doc strings on fields
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Opts {
-    pub endpoint: String,
     pub regions: Vec<EarthRegion>,
-    pub count: usize,
     pub attempts: usize,
+    #[serde(skip_serializing)]
     pub api_key: String,
     pub concurrency: usize,
     pub residential: NetworkPolicy,
     pub mobile: NetworkPolicy,
     pub proxy: NetworkPolicy,
+    pub isp_regex: Option<String>,
+    pub city: Option<String>,
+    pub node: Option<NodeSelector>,
     pub no_delay: bool,
+    pub tui: bool,
+    pub output: OutputFormat,
+    pub summary_by: SummaryGrouping,
+    pub sort: Option<ResultOrder>,
+    pub group_by: Option<ResultGrouping>,
+    pub filters: Vec<ResultFilter>,
+    pub only_failures: bool,
+    pub map: bool,
+    pub histogram: bool,
+    pub plain: bool,
+    pub compact: bool,
+    pub rtt_thresholds: Thresholds<3>,
+    pub loss_thresholds: Thresholds<2>,
+    pub csv_file: Option<PathBuf>,
+    pub junit_file: Option<PathBuf>,
+    pub html_file: Option<PathBuf>,
+    pub prometheus_file: Option<PathBuf>,
+    pub max_rtt: Option<f64>,
+    pub max_loss: Option<f64>,
+    pub influx: Option<InfluxOpts>,
+    pub no_history: bool,
+    pub job: JobKind,
 }
 
-impl Opts {
+/// Where to push ping results in InfluxDB line protocol once the run finishes.
+#[derive(Debug, Clone, Serialize)]
+pub struct InfluxOpts {
+    pub url: String,
+    pub org: String,
+    pub bucket: String,
+    #[serde(skip_serializing)]
+    pub token: Option<String>,
+}
+
+/// The kind of probe to send to every region, along with its own options.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum JobKind {
+    Icmp(IcmpOpts),
+    Dns(DnsOpts),
+    Http(HttpOpts),
+    Hls(HlsOpts),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IcmpOpts {
+    pub endpoints: Vec<String>,
+    pub count: usize,
+    pub payload_size: usize,
+    pub timeout: Option<u64>,
+    pub sweep: Option<PayloadSweep>,
+    pub watch: Option<WatchOpts>,
+    /// Print a reply line for every packet, ping style, instead of only the
+    /// aggregates the node reports.
+    pub emulate_ping: bool,
+}
+
+/// An increasing range of ICMP payload sizes to probe every region with.
+#[derive(Debug, Clone, Serialize)]
+pub struct PayloadSweep {
+    pub start: usize,
+    pub end: usize,
+    pub step: usize,
+}
+
+/// Keep pinging from a pinned node, mtr style, instead of running once.
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchOpts {
+    pub interval: Duration,
+    pub cycles: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsOpts {
+    pub hostname: String,
+    pub lookup_types: Vec<PerformDnsBodyConfigurationLookupTypesItem>,
+    pub dns_servers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HttpOpts {
+    pub method: String,
+    pub url: String,
+    pub headers: HashMap<String, String>,
+    pub status_codes: Vec<u16>,
+    pub regex: Option<String>,
+    pub body: Option<String>,
+    pub return_body: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HlsOpts {
+    pub url: String,
+    pub headers: HashMap<String, String>,
+}
+
+impl JobKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            JobKind::Icmp(_) => "icmp",
+            JobKind::Dns(_) => "dns",
+            JobKind::Http(_) => "http",
+            JobKind::Hls(_) => "hls",
+        }
+    }
+
+    /// What the jobs were sent to, for describing a run in a single line.
+    pub fn targets(&self) -> String {
+        match self {
+            JobKind::Icmp(opts) => opts.endpoints.join(", "),
+            JobKind::Dns(opts) => opts.hostname.clone(),
+            JobKind::Http(opts) => format!("{} {}", opts.method, opts.url),
+            JobKind::Hls(opts) => opts.url.clone(),
+        }
+    }
+}
+
+const MAX_PAYLOAD_SIZE: usize = 65500;
+
+const DNS_LOOKUP_TYPES: [&str; 7] = ["MX", "NS", "IP", "TXT", "SOA", "SRV", "TLSA"];
+
+/// The commands that would otherwise be taken for an endpoint to ping when
+/// an option comes before them.
+const SUBCOMMANDS: [&str; 4] = ["dns", "http", "hls", "history"];
+
+const HTTP_METHODS: [&str; 7] = ["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"];
+
+/// What bping was asked to do: send jobs to the Bitping API, or look back at
+/// the runs saved to the history database.
+#[derive(Debug, Clone)]
+pub enum Command {
+    Run(Box<Opts>),
+    History {
+        command: HistoryCommand,
+        plain: bool,
+    },
+}
+
+impl Command {
     pub fn parser() -> OptionParser<Self> {
-        let endpoint = bpaf::positional("endpoint")
-            .help("Specifies the endpoint (without http://) to ping. eg. bitping.com")
-            .guard(|s: &String| !s.is_empty(), "Endpoint cannot be empty");
+        let history = HistoryCommand::parser()
+            .to_options()
+            .descr("Look back at previous runs saved to the local history database.")
+            .command("history")
+            .map(|(command, plain)| Command::History { command, plain });
+
+        let run = Opts::parser().map(|opts| Command::Run(Box::new(opts)));
+
+        bpaf::construct!([history, run])
+            .to_options()
+            .descr("A command line utility to ping a website from anywhere in the world!")
+            .version(env!("CARGO_PKG_VERSION"))
+    }
+}
+
+impl Opts {
+    fn parser() -> impl Parser<Self> {
+        let dns = Self::with_job(DnsOpts::parser().map(JobKind::Dns))
+            .to_options()
+            .descr("Resolve DNS records for a hostname from anywhere in the world!")
+            .command("dns");
+
+        let http = Self::with_job(HttpOpts::parser().map(JobKind::Http))
+            .to_options()
+            .descr("Send an HTTP request to a URL from anywhere in the world!")
+            .command("http");
+
+        let hls = Self::with_job(HlsOpts::parser().map(JobKind::Hls))
+            .to_options()
+            .descr("Probe an HLS stream's playlists and fragments from anywhere in the world!")
+            .command("hls");
+
+        let icmp = Self::with_job(IcmpOpts::parser().map(JobKind::Icmp));
+
+        bpaf::construct!([dns, http, hls, icmp])
+    }
+
+    /// The number of jobs a run sends, used to size the progress bar. This is
+    /// unknown when watching a node until interrupted.
+    pub fn job_count(&self) -> Option<usize> {
+        let jobs_per_region = match &self.job {
+            JobKind::Icmp(opts) => opts.payload_sizes().len(),
+            _ => 1,
+        };
+        let cycles = match self.watch() {
+            Some(watch) => watch.cycles?,
+            None => 1,
+        };
 
+        Some(self.regions.len() * self.attempts * jobs_per_region * cycles)
+    }
+
+    /// Whether node results are held back until every job has finished, so
+    /// they can be sorted, grouped or filtered.
+    pub fn buffers_results(&self) -> bool {
+        self.sort.is_some()
+            || self.group_by.is_some()
+            || !self.filters.is_empty()
+            || self.only_failures
+    }
+
+    /// Whether any reports were asked for on top of the output format.
+    fn has_reports(&self) -> bool {
+        self.csv_file.is_some()
+            || self.junit_file.is_some()
+            || self.html_file.is_some()
+            || self.prometheus_file.is_some()
+            || self.influx.is_some()
+    }
+
+    /// Whether any options narrowing down which nodes run the jobs were given.
+    fn picks_nodes(&self) -> bool {
+        self.regions
+            .iter()
+            .any(|r| !matches!(r, EarthRegion::Anywhere))
+            || self.city.is_some()
+            || self.isp_regex.is_some()
+            || [&self.residential, &self.mobile, &self.proxy]
+                .iter()
+                .any(|policy| !matches!(policy, NetworkPolicy::Allowed))
+    }
+
+    /// Turns a `--node` identity into the region, city, ISP and network
+    /// options every kind of job is sent with, as the API can only pin jobs
+    /// to a node by its ID.
+    fn pin_to_identity(mut self) -> Self {
+        let Some(NodeSelector::Identity(identity)) = &self.node else {
+            return self;
+        };
+
+        if let Ok(alpha2) = keshvar::Alpha2::try_from(identity.country_code.as_str()) {
+            self.regions = vec![EarthRegion::Country(alpha2.to_country().alpha3())];
+        }
+        self.city = Some(identity.city.clone());
+        self.isp_regex = Some(identity.isp_regex());
+        (self.residential, self.mobile, self.proxy) = identity.network_policies();
+        self
+    }
+
+    /// The ID of the node `--node` pins jobs to, when it was given one.
+    pub fn node_id(&self) -> Option<String> {
+        match &self.node {
+            Some(NodeSelector::Id(id)) => Some(id.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn watch(&self) -> Option<&WatchOpts> {
+        match &self.job {
+            JobKind::Icmp(opts) => opts.watch.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Options shared by every job kind, so they can be passed after a subcommand too.
+    fn with_job(job: impl Parser<JobKind>) -> impl Parser<Self> {
         let regions = bpaf::long("regions")
                 .short('r')
                 .help(r#"Specifies the ISO 3166-1 country codes (alpha-2 or alpha-2) & continent names to send jobs to. Defaults to Anywhere.
@@ -69,12 +325,6 @@ impl Opts {
                 None =>vec![EarthRegion::Anywhere]
             });
 
-        let count = bpaf::long("count")
-            .short('c')
-            .help("Specifies the number of ICMP packets to send per country. Defaults to 3.")
-            .argument::<usize>("count")
-            .fallback(3);
-
         let attempts = bpaf::long("attempts")
             .short('a')
             .help("Specifies the number of ping attempts per country. Defaults to 1.")
@@ -109,28 +359,520 @@ impl Opts {
             .optional()
             .map(NetworkPolicy::from);
 
+        let isp_regex = bpaf::long("isp")
+            .help("Only use nodes whose ISP matches this regular expression. eg. --isp 'Comcast|Verizon'")
+            .argument::<String>("isp_regex")
+            .guard(
+                |r: &String| regress::Regex::new(r).is_ok(),
+                "ISP must be a valid regular expression",
+            )
+            .optional();
+
+        let city = bpaf::long("city")
+            .help("Only use nodes located in this city. eg. --city Sydney")
+            .argument::<String>("city")
+            .guard(|c: &String| !c.trim().is_empty(), "City cannot be empty")
+            .optional();
+
+        let node = bpaf::long("node")
+            .help("Send every job to one node instead of any node in the region, given its ID or the country:city:network:isp shown next to each result. eg. --node 'AU:Sydney:residential:Telstra'")
+            .argument::<NodeSelector>("node")
+            .optional();
+
         let no_delay = bpaf::long("no-delay")
             .help("Disable delays in output display")
             .switch();
 
+        let tui = bpaf::long("tui")
+            .help("Show a full-screen dashboard of the nodes as their results land, instead of a box per node.")
+            .switch();
+
+        let output = bpaf::long("output")
+            .short('o')
+            .help("Specifies the output format: text, json, ndjson, csv, prometheus, influx, geojson or markdown. Defaults to text.")
+            .argument::<OutputFormat>("format")
+            .fallback(OutputFormat::Text);
+
+        let summary_by = bpaf::long("summary-by")
+            .help("Groups the summary printed after a ping run by: region, continent, country or isp. Defaults to region.")
+            .argument::<SummaryGrouping>("group")
+            .fallback(SummaryGrouping::Region);
+
+        let sort = bpaf::long("sort")
+            .help("Displays node results once every job has finished, sorted by: rtt, loss, country or isp.")
+            .argument::<ResultOrder>("order")
+            .optional();
+
+        let group_by = bpaf::long("group-by")
+            .help("Displays node results once every job has finished, grouped by: continent, country, isp or network-type.")
+            .argument::<ResultGrouping>("group")
+            .optional();
+
+        let filters = bpaf::long("filter")
+            .help("Only displays nodes whose results match a condition on loss (%), rtt, min, max (ms), country, continent, city, isp, network or region. Can be repeated. eg. --filter 'loss>0' --filter 'isp~telstra'")
+            .argument::<ResultFilter>("condition")
+            .many();
+
+        let only_failures = bpaf::long("only-failures")
+            .help("Only displays nodes that returned an error or lost every packet.")
+            .switch();
+
+        let map = bpaf::long("map")
+            .help("Draws a world map of the nodes, coloured by RTT and packet loss, after the run or below the --watch table.")
+            .switch();
+
+        let histogram = bpaf::long("histogram")
+            .help("Prints a histogram of node RTTs for each region and overall after the run, along with how widely each region's nodes spread.")
+            .switch();
+
+        let plain = plain();
+
+        let compact = bpaf::long("compact")
+            .help("Prints one aligned line per node result, like fping, instead of a box per node.")
+            .switch();
+
+        let rtt_thresholds = bpaf::long("rtt-thresholds")
+            .help("The RTTs in ms above which results turn yellow, orange and red. Defaults to 100,200,300.")
+            .argument::<Thresholds<3>>("ms,ms,ms")
+            .fallback(Thresholds([100.0, 200.0, 300.0]));
+
+        let loss_thresholds = bpaf::long("loss-thresholds")
+            .help("The packet loss percentages above which results turn yellow and red. Defaults to 0,10.")
+            .argument::<Thresholds<2>>("%,%")
+            .fallback(Thresholds([0.0, 10.0]));
+
+        let csv_file = bpaf::long("csv-file")
+            .help("Also writes ping results to this file as CSV, one row per node result.")
+            .argument::<PathBuf>("path")
+            .optional();
+
+        let junit_file = bpaf::long("junit")
+            .help("Writes a JUnit XML report to this file, with a test case for every endpoint and region.")
+            .argument::<PathBuf>("path")
+            .optional();
+
+        let html_file = bpaf::long("html")
+            .help("Writes a self contained HTML report of the results to this file.")
+            .argument::<PathBuf>("path")
+            .optional();
+
+        let prometheus_file = bpaf::long("prometheus-file")
+            .help("Writes OpenMetrics gauges of the results to this file, replacing it in one go so node_exporter never reads it half written.")
+            .argument::<PathBuf>("path")
+            .optional();
+
+        let max_rtt = bpaf::long("max-rtt")
+            .help("Fails a node in the JUnit report when its average round trip time exceeds this many milliseconds.")
+            .argument::<f64>("ms")
+            .guard(|ms| *ms > 0.0, "Maximum RTT must be greater than 0")
+            .optional();
+
+        let max_loss = bpaf::long("max-loss")
+            .help("Fails a node in the JUnit report when its packet loss exceeds this percentage.")
+            .argument::<f64>("percent")
+            .guard(
+                |p| (0.0..=100.0).contains(p),
+                "Maximum packet loss must be between 0 and 100",
+            )
+            .optional();
+
+        let influx = InfluxOpts::parser();
+
+        let no_history = bpaf::long("no-history")
+            .help("Don't save this run to the local history database.")
+            .switch();
+
         bpaf::construct!(Opts {
             regions,
-            count,
             attempts,
             concurrency,
             api_key,
             residential,
             mobile,
             proxy,
+            isp_regex,
+            city,
+            node,
             no_delay,
-            endpoint,
+            tui,
+            output,
+            summary_by,
+            sort,
+            group_by,
+            filters,
+            only_failures,
+            map,
+            histogram,
+            plain,
+            compact,
+            rtt_thresholds,
+            loss_thresholds,
+            csv_file,
+            junit_file,
+            html_file,
+            prometheus_file,
+            max_rtt,
+            max_loss,
+            influx,
+            no_history,
+            job,
+        })
+        .guard(
+            |o| !matches!(o.node, Some(NodeSelector::Identity(_))) || !o.picks_nodes(),
+            "--node country:city:network:isp already picks the country, city, ISP and network, so it can't be combined with -r, --city, --isp, --residential, --mobile or --proxy",
+        )
+        .map(Opts::pin_to_identity)
+        .guard(
+            |o| o.watch().is_none() || o.node.is_some(),
+            "--watch requires --node to pin the probes to a single node",
+        )
+        .guard(
+            |o| {
+                o.watch().is_none() || matches!(o.output, OutputFormat::Text | OutputFormat::Ndjson)
+            },
+            "--watch only supports text or ndjson output",
+        )
+        .guard(
+            |o| o.watch().is_none() || !o.has_reports(),
+            "--watch cannot be combined with --csv-file, --junit, --html, --prometheus-file or --influx-url",
+        )
+        .guard(
+            |o| !o.tui || matches!(o.job, JobKind::Icmp(_)),
+            "--tui is only supported for ICMP pings",
+        )
+        .guard(
+            |o| !o.tui || (o.output == OutputFormat::Text && o.watch().is_none()),
+            "--tui cannot be combined with --output or --watch",
+        )
+        .guard(
+            |o| {
+                !o.buffers_results()
+                    || (o.output == OutputFormat::Text && o.watch().is_none() && !o.tui)
+            },
+            "--sort, --group-by, --filter and --only-failures are only supported for text output without --watch or --tui",
+        )
+        .guard(
+            |o| {
+                !(o.map || o.histogram)
+                    || (matches!(o.job, JobKind::Icmp(_))
+                        && o.output == OutputFormat::Text
+                        && !o.tui)
+            },
+            "--map and --histogram are only supported for ICMP pings with text output",
+        )
+        .guard(
+            |o| {
+                !o.compact
+                    || (matches!(o.job, JobKind::Icmp(_))
+                        && o.output == OutputFormat::Text
+                        && o.watch().is_none()
+                        && !o.tui)
+            },
+            "--compact is only supported for ICMP pings with text output, without --watch or --tui",
+        )
+        .guard(
+            |o| matches!(o.job, JobKind::Icmp(_)) || !o.output.is_icmp_only(),
+            "This output format is only supported for ICMP pings",
+        )
+        .guard(
+            |o| matches!(o.job, JobKind::Icmp(_)) || !o.has_reports(),
+            "--csv-file, --junit, --html, --prometheus-file and --influx-url are only supported for ICMP pings",
+        )
+    }
+}
+
+impl InfluxOpts {
+    fn parser() -> impl Parser<Option<Self>> {
+        let url = bpaf::long("influx-url")
+            .help("Pushes ping results to the InfluxDB v2 server at this URL once the run finishes. eg. --influx-url http://localhost:8086")
+            .argument::<String>("url")
+            .guard(
+                |u: &String| reqwest::Url::parse(u).is_ok(),
+                "InfluxDB URL must be a valid URL",
+            );
+
+        let org = bpaf::long("influx-org")
+            .help("Specifies the InfluxDB organization to write to.")
+            .env("INFLUX_ORG")
+            .argument::<String>("org");
+
+        let bucket = bpaf::long("influx-bucket")
+            .help("Specifies the InfluxDB bucket to write to.")
+            .env("INFLUX_BUCKET")
+            .argument::<String>("bucket");
+
+        let token = bpaf::long("influx-token")
+            .help("Specifies the InfluxDB API token. Can also be set using the INFLUX_TOKEN environment variable.")
+            .env("INFLUX_TOKEN")
+            .argument::<String>("token")
+            .optional();
+
+        bpaf::construct!(InfluxOpts {
+            url,
+            org,
+            bucket,
+            token
+        })
+        .optional()
+    }
+}
+
+impl IcmpOpts {
+    fn parser() -> impl Parser<Self> {
+        let count = bpaf::long("count")
+            .short('c')
+            .help("Specifies the number of ICMP packets to send per country. Defaults to 3.")
+            .argument::<usize>("count")
+            .fallback(3);
+
+        let payload_size = bpaf::long("size")
+            .short('s')
+            .help("Specifies the number of data bytes to send in each ICMP packet. Defaults to 56.")
+            .argument::<usize>("size")
+            .guard(
+                |s| *s <= MAX_PAYLOAD_SIZE,
+                "Size cannot be larger than 65500 bytes",
+            )
+            .fallback(56);
+
+        let timeout = bpaf::long("timeout")
+            .short('W')
+            .help("Specifies how long in milliseconds to wait for each reply. Defaults to the node's timeout.")
+            .argument::<u64>("timeout")
+            .optional();
+
+        let sweep_range = bpaf::long("sweep-size")
+            .help("Probes each region with increasing payload sizes and reports where packet loss begins. eg. --sweep-size 56..1472")
+            .argument::<String>("range")
+            .parse(|v| parse_sweep_range(&v))
+            .optional();
+
+        let sweep_step = bpaf::long("sweep-step")
+            .help("Specifies how many bytes to grow the payload by in each sweep step. Defaults to 256.")
+            .argument::<usize>("step")
+            .guard(|s| *s > 0, "Sweep step must be greater than 0")
+            .fallback(256);
+
+        let sweep = bpaf::construct!(sweep_range, sweep_step)
+            .map(|(range, step)| range.map(|(start, end)| PayloadSweep { start, end, step }));
+
+        let watch = bpaf::long("watch")
+            .help(
+                "Keep pinging from the node given by --node and show a rolling loss/latency table.",
+            )
+            .switch();
+
+        let interval = bpaf::long("interval")
+            .short('i')
+            .help("Specifies the seconds to wait between --watch cycles. Defaults to 1.")
+            .argument::<f64>("interval")
+            .guard(
+                |i| Duration::try_from_secs_f64(*i).is_ok(),
+                "Interval must be a finite number of seconds, no less than 0",
+            )
+            .fallback(1.0);
+
+        let cycles = bpaf::long("cycles")
+            .help("Specifies how many --watch cycles to run before exiting. Defaults to running until interrupted.")
+            .argument::<usize>("cycles")
+            .optional();
+
+        let watch = bpaf::construct!(watch, interval, cycles).map(|(watch, interval, cycles)| {
+            watch.then(|| WatchOpts {
+                interval: Duration::from_secs_f64(interval),
+                cycles,
+            })
+        });
+
+        let emulate_ping = bpaf::long("emulate-ping")
+            .help("Print a line for every packet like ping does. The times are spread between the min and max the node reports, not measured.")
+            .switch();
+
+        let endpoints = bpaf::positional("endpoint")
+            .help("Specifies the endpoints (without http://) to ping. Every endpoint is pinged from the same nodes. eg. bitping.com")
+            .guard(|s: &String| !s.is_empty(), "Endpoint cannot be empty")
+            .some("At least one endpoint is required")
+            .guard(
+                |e| !SUBCOMMANDS.contains(&e[0].as_str()),
+                "Options must follow the subcommand, eg. bping dns -r AU example.com",
+            );
+
+        bpaf::construct!(IcmpOpts {
+            count,
+            payload_size,
+            timeout,
+            sweep,
+            watch,
+            emulate_ping,
+            endpoints,
+        })
+        .guard(
+            |o| o.sweep.is_none() || o.watch.is_none(),
+            "--watch cannot be combined with --sweep-size",
+        )
+    }
+
+    /// The payload sizes to probe each region with.
+    pub fn payload_sizes(&self) -> Vec<usize> {
+        let Some(sweep) = &self.sweep else {
+            return vec![self.payload_size];
+        };
+
+        let mut sizes: Vec<usize> = (sweep.start..sweep.end).step_by(sweep.step).collect();
+        sizes.push(sweep.end);
+        sizes
+    }
+}
+
+/// `--plain`, which runs and `bping history` both take.
+pub(super) fn plain() -> impl Parser<bool> {
+    bpaf::long("plain")
+        .help("Prints plain ASCII without colours, box drawing characters or emoji, for logs and screen readers. Colours are also turned off by setting NO_COLOR.")
+        .switch()
+}
+
+fn parse_sweep_range(range: &str) -> Result<(usize, usize)> {
+    let Some((start, end)) = range.split_once("..") else {
+        eyre::bail!("Sweep range '{range}' must be in the form start..end");
+    };
+    let (start, end) = (start.trim().parse::<usize>()?, end.trim().parse::<usize>()?);
+
+    if start >= end {
+        eyre::bail!("Sweep range start must be smaller than its end");
+    }
+    if end > MAX_PAYLOAD_SIZE {
+        eyre::bail!("Sweep range cannot go past {MAX_PAYLOAD_SIZE} bytes");
+    }
+
+    Ok((start, end))
+}
+
+impl DnsOpts {
+    fn parser() -> impl Parser<Self> {
+        let lookup_types = bpaf::long("type")
+            .short('t')
+            .help("Specifies the comma separated record types to look up (MX, NS, IP, TXT, SOA, SRV, TLSA). Defaults to all.")
+            .argument::<String>("types")
+            .parse(|v| parse_lookup_types(&v))
+            .fallback(vec![]);
+
+        let dns_servers = bpaf::long("dns-server")
+            .help("Specifies a DNS server to resolve against instead of the node's default. Can be repeated.")
+            .argument::<String>("dns_server")
+            .many();
+
+        let hostname = bpaf::positional("hostname")
+            .help("Specifies the hostname to resolve. eg. bitping.com")
+            .guard(|s: &String| !s.is_empty(), "Hostname cannot be empty");
+
+        bpaf::construct!(DnsOpts {
+            lookup_types,
+            dns_servers,
+            hostname,
         })
-        .to_options()
-        .descr("A command line utility to ping a website from anywhere in the world!")
-        .version(env!("CARGO_PKG_VERSION"))
     }
 }
 
+impl HttpOpts {
+    fn parser() -> impl Parser<Self> {
+        let headers = headers();
+
+        let status_codes = bpaf::long("expect-status")
+            .help("Specifies the comma separated status codes the response is expected to have. eg. 200,301")
+            .argument::<String>("status_codes")
+            .parse(|v| {
+                v.split(',')
+                    .map(|code| code.trim().parse::<u16>())
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .fallback(vec![]);
+
+        let regex = bpaf::long("regex")
+            .help("Specifies a regular expression to match against the response body.")
+            .argument::<String>("regex")
+            .guard(
+                |r: &String| regress::Regex::new(r).is_ok(),
+                "Regex is not a valid regular expression",
+            )
+            .optional();
+
+        let body = bpaf::long("body")
+            .help("Specifies the request body to send.")
+            .argument::<String>("body")
+            .optional();
+
+        let return_body = bpaf::long("return-body")
+            .help("Display the response body returned by each node")
+            .switch();
+
+        let method = bpaf::positional::<String>("method")
+            .help("Specifies the HTTP method to use. eg. GET")
+            .map(|m| m.to_uppercase())
+            .guard(
+                |m: &String| HTTP_METHODS.contains(&m.as_str()),
+                "Method must be one of GET, HEAD, POST, PUT, PATCH, DELETE or OPTIONS",
+            );
+
+        let url = bpaf::positional("url")
+            .help("Specifies the URL to request. eg. https://bitping.com")
+            .guard(|s: &String| !s.is_empty(), "URL cannot be empty");
+
+        bpaf::construct!(HttpOpts {
+            headers,
+            status_codes,
+            regex,
+            body,
+            return_body,
+            method,
+            url,
+        })
+    }
+}
+
+impl HlsOpts {
+    fn parser() -> impl Parser<Self> {
+        let headers = headers();
+
+        let url = bpaf::positional("url")
+            .help("Specifies the URL of the master or media playlist. eg. https://cdn.example.com/master.m3u8")
+            .guard(|s: &String| !s.is_empty(), "URL cannot be empty");
+
+        bpaf::construct!(HlsOpts { headers, url })
+    }
+}
+
+/// Repeatable `-H 'Name: value'` request headers.
+fn headers() -> impl Parser<HashMap<String, String>> {
+    bpaf::long("header")
+        .short('H')
+        .help("Specifies a request header in the form 'Name: value'. Can be repeated.")
+        .argument::<String>("header")
+        .parse(|h| match h.split_once(':') {
+            Some((name, value)) if !name.trim().is_empty() => {
+                Ok((name.trim().to_string(), value.trim().to_string()))
+            }
+            _ => Err(format!("Header '{h}' must be in the form 'Name: value'")),
+        })
+        .many()
+        .map(|headers| headers.into_iter().collect())
+}
+
+fn parse_lookup_types(types: &str) -> Result<Vec<PerformDnsBodyConfigurationLookupTypesItem>> {
+    types
+        .split(',')
+        .map(|t| {
+            let t = t.trim().to_uppercase();
+            if !DNS_LOOKUP_TYPES.contains(&t.as_str()) {
+                eyre::bail!(
+                    "Unknown DNS record type '{t}'. Expected one of {}",
+                    DNS_LOOKUP_TYPES.join(", ")
+                );
+            }
+            Ok(t.parse()?)
+        })
+        .collect()
+}
+
 #[derive(Clone, Debug)]
 pub enum EarthRegion {
     Continent(keshvar::Continent),
@@ -139,25 +881,13 @@ pub enum EarthRegion {
 }
 
 impl EarthRegion {
-    pub fn get_codes(
-        &self,
-    ) -> Result<
-        (
-            Option<PerformIcmpBodyCountryCode>,
-            Option<PerformIcmpBodyContinentCode>,
-        ),
-        Errors,
-    > {
-        Ok(match self {
-            EarthRegion::Country(c) => (
-                Some(PerformIcmpBodyCountryCode::from_str(
-                    &c.to_country().alpha2().to_string(),
-                )?),
-                None,
-            ),
+    /// The alpha-2 country code or continent code the region targets.
+    pub fn codes(&self) -> (Option<String>, Option<&'static str>) {
+        match self {
+            EarthRegion::Country(c) => (Some(c.to_country().alpha2().to_string()), None),
             EarthRegion::Continent(con) => (
                 None,
-                Some(PerformIcmpBodyContinentCode::from_str(match con {
+                Some(match con {
                     keshvar::Continent::Africa => "AF",
                     keshvar::Continent::Antarctica => "AN",
                     keshvar::Continent::Asia => "AS",
@@ -165,10 +895,42 @@ impl EarthRegion {
                     keshvar::Continent::Europe => "EU",
                     keshvar::Continent::NorthAmerica => "NA",
                     keshvar::Continent::SouthAmerica => "SA",
-                })?),
+                }),
             ),
-            _ => (None, None),
-        })
+            EarthRegion::Anywhere => (None, None),
+        }
+    }
+
+    /// Resolves the region into the country/continent code pair of whichever
+    /// request body is being built, as every job endpoint has its own code types.
+    pub fn get_codes<Country, Continent>(
+        &self,
+    ) -> Result<(Option<Country>, Option<Continent>), Errors>
+    where
+        Country: FromStr<Err = ConversionError>,
+        Continent: FromStr<Err = ConversionError>,
+    {
+        let (country, continent) = self.codes();
+
+        Ok((
+            country.as_deref().map(Country::from_str).transpose()?,
+            continent.map(Continent::from_str).transpose()?,
+        ))
+    }
+
+    /// A name for the region in reports, as it would otherwise display as an
+    /// empty string when no region was requested.
+    pub fn name(&self) -> String {
+        match self {
+            EarthRegion::Anywhere => "Anywhere".to_string(),
+            region => region.to_string(),
+        }
+    }
+}
+
+impl Serialize for EarthRegion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name())
     }
 }
 
@@ -231,7 +993,7 @@ pub fn parse_alpha_codes(regions: &str) -> Result<Vec<EarthRegion>> {
 
         match region_part.to_lowercase().as_str() {
             "america" => {
-                println!("Assuming North and South America.");
+                eprintln!("Assuming North and South America.");
                 regions.extend_from_slice(&[
                     EarthRegion::Continent(Continent::NorthAmerica),
                     EarthRegion::Continent(Continent::SouthAmerica),
@@ -0,0 +1,192 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Serialize, Serializer};
+
+/// How the results of a run are written to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Box drawn results for people reading a terminal.
+    Text,
+    /// A single JSON document written once every job has finished.
+    Json,
+    /// One JSON line per node result, written as soon as it lands.
+    Ndjson,
+    /// One row per node result written once every job has finished.
+    Csv,
+    /// OpenMetrics gauges for node_exporter's textfile collector.
+    Prometheus,
+    /// InfluxDB line protocol, one point per node result.
+    Influx,
+    /// A GeoJSON FeatureCollection with a point for every node result.
+    Geojson,
+    /// A GitHub flavoured markdown table for pull requests and chat.
+    Markdown,
+}
+
+impl OutputFormat {
+    /// Whether the format is built around ping statistics, and so can't be
+    /// used for the other kinds of probes.
+    pub fn is_icmp_only(&self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Csv
+                | OutputFormat::Prometheus
+                | OutputFormat::Influx
+                | OutputFormat::Geojson
+                | OutputFormat::Markdown
+        )
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            "csv" => Ok(OutputFormat::Csv),
+            "prometheus" => Ok(OutputFormat::Prometheus),
+            "influx" => Ok(OutputFormat::Influx),
+            "geojson" => Ok(OutputFormat::Geojson),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            _ => Err(format!(
+                "Unknown output format '{s}'. Expected text, json, ndjson, csv, prometheus, influx, geojson or markdown"
+            )),
+        }
+    }
+}
+
+/// What the end of run summary groups node results by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryGrouping {
+    /// The region the job was sent to.
+    Region,
+    /// The continent the node is located in.
+    Continent,
+    /// The country the node is located in.
+    Country,
+    /// The node's ISP.
+    Isp,
+}
+
+impl FromStr for SummaryGrouping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "region" => Ok(SummaryGrouping::Region),
+            "continent" => Ok(SummaryGrouping::Continent),
+            "country" => Ok(SummaryGrouping::Country),
+            "isp" => Ok(SummaryGrouping::Isp),
+            _ => Err(format!(
+                "Unknown summary grouping '{s}'. Expected region, continent, country or isp"
+            )),
+        }
+    }
+}
+
+/// What order node results are displayed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultOrder {
+    /// Fastest first, with unreachable nodes last.
+    Rtt,
+    /// Least packet loss first.
+    Loss,
+    Country,
+    Isp,
+}
+
+impl FromStr for ResultOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rtt" => Ok(ResultOrder::Rtt),
+            "loss" => Ok(ResultOrder::Loss),
+            "country" => Ok(ResultOrder::Country),
+            "isp" => Ok(ResultOrder::Isp),
+            _ => Err(format!(
+                "Unknown sort order '{s}'. Expected rtt, loss, country or isp"
+            )),
+        }
+    }
+}
+
+/// What node results are grouped under when displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResultGrouping {
+    Continent,
+    Country,
+    Isp,
+    /// Mobile, proxy, residential or datacenter.
+    NetworkType,
+}
+
+impl FromStr for ResultGrouping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "continent" => Ok(ResultGrouping::Continent),
+            "country" => Ok(ResultGrouping::Country),
+            "isp" => Ok(ResultGrouping::Isp),
+            "network-type" | "network" => Ok(ResultGrouping::NetworkType),
+            _ => Err(format!(
+                "Unknown grouping '{s}'. Expected continent, country, isp or network-type"
+            )),
+        }
+    }
+}
+
+/// Ascending limits that split values into colour bands, eg. `100,200,300`
+/// for RTTs in milliseconds. Values above the last limit get the worst colour.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thresholds<const N: usize>(pub [f64; N]);
+
+impl<const N: usize> Thresholds<N> {
+    /// How many limits the value is above, from 0 (best) to `N` (worst).
+    pub fn band(&self, value: f64) -> usize {
+        self.0.iter().filter(|limit| value > **limit).count()
+    }
+}
+
+impl<const N: usize> FromStr for Thresholds<N> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let limits = s
+            .split(',')
+            .map(|limit| limit.trim().trim_end_matches("ms").trim_end_matches('%'))
+            .map(|limit| limit.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("Thresholds '{s}' must be numbers separated by commas"))?;
+
+        let limits: [f64; N] = limits
+            .try_into()
+            .map_err(|_| format!("Expected {N} thresholds separated by commas, got '{s}'"))?;
+        if limits.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Err(format!("Thresholds '{s}' must be in ascending order"));
+        }
+
+        Ok(Thresholds(limits))
+    }
+}
+
+impl<const N: usize> Display for Thresholds<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let limits: Vec<String> = self.0.iter().map(|limit| limit.to_string()).collect();
+        write!(f, "{}", limits.join(","))
+    }
+}
+
+impl<const N: usize> Serialize for Thresholds<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
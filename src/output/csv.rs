@@ -0,0 +1,106 @@
+use std::io::Write;
+
+use color_eyre::eyre::{Context, Result};
+use serde::Serialize;
+
+use crate::options::EarthRegion;
+
+use super::{icmp_rows, RunRecord};
+
+/// The header of the CSV output, matching the fields of [`CsvRow`]. It's
+/// written up front so a run without results still leaves a valid file.
+const COLUMNS: [&str; 22] = [
+    "region",
+    "endpoint",
+    "payload_size",
+    "country",
+    "region_name",
+    "city",
+    "lat",
+    "lon",
+    "isp",
+    "os",
+    "mobile",
+    "proxy",
+    "residential",
+    "ip_address",
+    "packets_sent",
+    "packets_recv",
+    "packet_loss",
+    "min",
+    "avg",
+    "max",
+    "std_dev",
+    "error",
+];
+
+/// A row of the CSV output, flattening an [`IcmpRow`](super::IcmpRow).
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    region: &'a EarthRegion,
+    endpoint: &'a str,
+    payload_size: usize,
+    country: Option<&'a str>,
+    region_name: Option<&'a str>,
+    city: Option<&'a str>,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    isp: Option<&'a str>,
+    os: Option<&'a str>,
+    mobile: Option<bool>,
+    proxy: Option<bool>,
+    residential: Option<bool>,
+    ip_address: Option<&'a str>,
+    packets_sent: Option<f64>,
+    packets_recv: Option<f64>,
+    packet_loss: Option<f64>,
+    min: Option<f64>,
+    avg: Option<f64>,
+    max: Option<f64>,
+    std_dev: Option<f64>,
+    error: Option<&'a str>,
+}
+
+pub fn write(run: &RunRecord, writer: impl Write) -> Result<()> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(writer);
+    writer
+        .write_record(COLUMNS)
+        .context("Failed to write CSV output")?;
+
+    for row in icmp_rows(&run.responses) {
+        let node_info = row.node_info;
+        let result = row.item.result.as_ref();
+
+        writer
+            .serialize(CsvRow {
+                region: row.region(),
+                endpoint: &row.item.endpoint,
+                payload_size: row.payload_size,
+                country: node_info.map(|n| n.country_code.as_str()),
+                region_name: node_info.map(|n| n.region_name.as_str()),
+                city: node_info.map(|n| n.city.as_str()),
+                lat: node_info.map(|n| n.lat),
+                lon: node_info.map(|n| n.lon),
+                isp: node_info.map(|n| n.isp.as_str()),
+                os: node_info.map(|n| n.operating_system.as_str()),
+                mobile: node_info.map(|n| n.mobile),
+                proxy: node_info.map(|n| n.proxy),
+                residential: node_info.map(|n| n.residential),
+                ip_address: result.map(|r| r.ip_address.as_str()),
+                packets_sent: result.map(|r| r.packets_sent),
+                packets_recv: result.map(|r| r.packets_recv),
+                packet_loss: result.map(|r| r.packet_loss),
+                min: result.map(|r| r.min),
+                avg: result.map(|r| r.avg),
+                max: result.map(|r| r.max),
+                std_dev: result.map(|r| r.std_dev),
+                error: row.item.error.as_deref(),
+            })
+            .context("Failed to write CSV output")?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use std::io::Write;
+
+use color_eyre::eyre::{Context, Result};
+use serde::Serialize;
+
+use crate::options::EarthRegion;
+
+use super::{icmp_rows, RunRecord};
+
+#[derive(Serialize)]
+#[serde(tag = "type")]
+struct FeatureCollection<'a> {
+    features: Vec<Feature<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type")]
+struct Feature<'a> {
+    geometry: Point,
+    properties: Properties<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type")]
+struct Point {
+    /// GeoJSON positions are longitude first.
+    coordinates: [f64; 2],
+}
+
+/// What a feature says about the [`IcmpRow`](super::IcmpRow) it was made
+/// from, beyond its location.
+#[derive(Serialize)]
+struct Properties<'a> {
+    endpoint: &'a str,
+    region: &'a EarthRegion,
+    payload_size: usize,
+    country: &'a str,
+    continent: &'a str,
+    region_name: &'a str,
+    city: &'a str,
+    isp: &'a str,
+    network_type: &'a str,
+    ip_address: Option<&'a str>,
+    rtt_min: Option<f64>,
+    rtt_avg: Option<f64>,
+    rtt_max: Option<f64>,
+    rtt_std_dev: Option<f64>,
+    packet_loss: Option<f64>,
+    error: Option<&'a str>,
+}
+
+/// Writes a point for every node result. Results without node info are left
+/// out as there is nowhere to put them on a map.
+pub fn write(run: &RunRecord, mut writer: impl Write) -> Result<()> {
+    let features: Vec<Feature> = icmp_rows(&run.responses)
+        .filter_map(|row| {
+            let node_info = row.node_info?;
+            let result = row.item.result.as_ref();
+
+            Some(Feature {
+                geometry: Point {
+                    coordinates: [node_info.lon, node_info.lat],
+                },
+                properties: Properties {
+                    endpoint: &row.item.endpoint,
+                    region: row.region(),
+                    payload_size: row.payload_size,
+                    country: &node_info.country_code,
+                    continent: &node_info.continent_code,
+                    region_name: &node_info.region_name,
+                    city: &node_info.city,
+                    isp: &node_info.isp,
+                    network_type: node_info.network_type(),
+                    ip_address: result.map(|r| r.ip_address.as_str()),
+                    rtt_min: result.map(|r| r.min),
+                    rtt_avg: result.map(|r| r.avg),
+                    rtt_max: result.map(|r| r.max),
+                    rtt_std_dev: result.map(|r| r.std_dev),
+                    packet_loss: result.map(|r| r.packet_loss),
+                    error: row.item.error.as_deref(),
+                },
+            })
+        })
+        .collect();
+
+    serde_json::to_writer_pretty(&mut writer, &FeatureCollection { features })
+        .context("Failed to write GeoJSON output")?;
+    writeln!(writer)?;
+
+    Ok(())
+}
@@ -0,0 +1,416 @@
+use std::{collections::BTreeMap, fmt::Write as _, io::Write};
+
+use color_eyre::eyre::{Context, Result};
+
+use crate::{
+    display::land_spans,
+    models::{types::PerformIcmpResponseResultsItem, NodeInfo},
+    options::{EarthRegion, JobKind, Opts},
+    stats::{IcmpAggregates, RunAggregates},
+};
+
+use super::{icmp_rows, IcmpRow, RunRecord};
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2rem; color: #1f2328; background: #fff; }
+h1 { margin-bottom: 0.25rem; }
+h2 { margin-top: 2.5rem; border-bottom: 1px solid #d0d7de; padding-bottom: 0.3rem; }
+.meta { color: #59636e; margin-top: 0; }
+table { border-collapse: collapse; font-size: 0.9rem; }
+th, td { padding: 0.35rem 0.7rem; border: 1px solid #d0d7de; text-align: left; white-space: nowrap; }
+th { background: #f6f8fa; }
+th[data-sort] { cursor: pointer; user-select: none; }
+th[data-sort]::after { content: " \2195"; color: #8c959f; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+tr.failed td { background: #ffebe9; }
+.swatch { display: inline-block; width: 0.8rem; height: 0.8rem; border-radius: 50%; vertical-align: middle; margin-right: 0.3rem; }
+.legend span { margin-right: 1rem; }
+svg text { font-size: 11px; fill: #59636e; }
+"#;
+
+const SCRIPT: &str = r#"
+document.querySelectorAll("table.sortable").forEach(function (table) {
+  table.querySelectorAll("th[data-sort]").forEach(function (th, column) {
+    var ascending = true;
+    th.addEventListener("click", function () {
+      var body = table.tBodies[0];
+      var rows = Array.prototype.slice.call(body.rows);
+      rows.sort(function (a, b) {
+        var x = a.cells[column].dataset.value, y = b.cells[column].dataset.value;
+        var result = th.dataset.sort === "number"
+          ? (x === "" ? Infinity : parseFloat(x)) - (y === "" ? Infinity : parseFloat(y))
+          : x.localeCompare(y);
+        return ascending ? result : -result;
+      });
+      ascending = !ascending;
+      rows.forEach(function (row) { body.appendChild(row); });
+    });
+  });
+});
+"#;
+
+/// The colours nodes are drawn in as their RTT crosses each of
+/// `--rtt-thresholds`, matching the terminal's green to red.
+const RTT_COLOURS: [&str; 4] = ["#2da44e", "#bf8700", "#e16f24", "#cf222e"];
+const FAILED_COLOUR: &str = "#6e7781";
+
+/// The colour a row's node is drawn in.
+fn row_colour(config: &Opts, row: &IcmpRow) -> &'static str {
+    match row.item.rtt_avg() {
+        Some(avg) => rtt_colour(config, avg),
+        None => FAILED_COLOUR,
+    }
+}
+
+/// Writes a self contained HTML report of the run, with everything inlined so
+/// it can be opened offline or attached to a ticket.
+pub fn write(config: &Opts, run: &RunRecord, mut writer: impl Write) -> Result<()> {
+    let rows: Vec<IcmpRow> = icmp_rows(&run.responses).collect();
+
+    let mut html = String::new();
+    writeln!(html, "<!DOCTYPE html>")?;
+    writeln!(html, r#"<html lang="en"><head><meta charset="utf-8">"#)?;
+    writeln!(
+        html,
+        "<title>bping report</title><style>{STYLE}</style></head><body>"
+    )?;
+
+    write_overview(&mut html, config, run)?;
+    write_continents(&mut html, &rows)?;
+    write_histogram(&mut html, config, &rows)?;
+    write_map(&mut html, config, &rows)?;
+    write_results(&mut html, &rows)?;
+
+    writeln!(html, "<script>{SCRIPT}</script></body></html>")?;
+
+    writer
+        .write_all(html.as_bytes())
+        .context("Failed to write HTML report")?;
+    Ok(())
+}
+
+fn write_overview(html: &mut String, config: &Opts, run: &RunRecord) -> Result<()> {
+    let endpoints = match &config.job {
+        JobKind::Icmp(opts) => opts.endpoints.join(", "),
+        _ => String::new(),
+    };
+    let regions: Vec<String> = config.regions.iter().map(EarthRegion::name).collect();
+
+    writeln!(html, "<h1>bping {}</h1>", escape(&endpoints))?;
+    writeln!(
+        html,
+        r#"<p class="meta">From {} &middot; started {} &middot; took {:.1}s</p>"#,
+        escape(&regions.join(", ")),
+        run.started_at.format("%Y-%m-%d %H:%M:%S UTC"),
+        (run.finished_at - run.started_at).num_milliseconds() as f64 / 1000.0
+    )?;
+
+    let aggregates = RunAggregates::from_responses(&run.responses);
+    writeln!(html, "<h2>Summary</h2>")?;
+    write_aggregates_table(html, "Endpoint", aggregates.endpoints.iter())?;
+    Ok(())
+}
+
+fn write_continents(html: &mut String, rows: &[IcmpRow]) -> Result<()> {
+    let mut continents: BTreeMap<String, Vec<&PerformIcmpResponseResultsItem>> = BTreeMap::new();
+    for row in rows {
+        let continent = row.node_info.map_or("Unknown", NodeInfo::continent_name);
+        continents
+            .entry(continent.to_string())
+            .or_default()
+            .push(row.item);
+    }
+
+    let aggregates: Vec<(String, IcmpAggregates)> = continents
+        .into_iter()
+        .map(|(continent, items)| (continent, IcmpAggregates::from_results(items)))
+        .collect();
+
+    writeln!(html, "<h2>By Continent</h2>")?;
+    write_aggregates_table(html, "Continent", aggregates.iter().map(|(c, a)| (c, a)))
+}
+
+fn write_aggregates_table<'a>(
+    html: &mut String,
+    label: &str,
+    groups: impl Iterator<Item = (&'a String, &'a IcmpAggregates)>,
+) -> Result<()> {
+    writeln!(html, "<table><thead><tr>")?;
+    writeln!(
+        html,
+        "<th>{label}</th><th>Results</th><th>Reachable</th><th>Errors</th><th>Loss</th><th>Min</th><th>Avg</th><th>Max</th>"
+    )?;
+    writeln!(html, "</tr></thead><tbody>")?;
+
+    for (name, a) in groups {
+        writeln!(
+            html,
+            r#"<tr><td>{}</td><td class="num">{}</td><td class="num">{}</td><td class="num">{}</td><td class="num">{}</td><td class="num">{}</td><td class="num">{}</td><td class="num">{}</td></tr>"#,
+            escape(name),
+            a.results,
+            a.successful,
+            a.errors,
+            a.packet_loss
+                .map_or("-".to_string(), |l| format!("{:.1}%", l * 100.0)),
+            format_ms(a.rtt_min),
+            format_ms(a.rtt_avg),
+            format_ms(a.rtt_max),
+        )?;
+    }
+
+    writeln!(html, "</tbody></table>")?;
+    Ok(())
+}
+
+fn write_histogram(html: &mut String, config: &Opts, rows: &[IcmpRow]) -> Result<()> {
+    let rtts: Vec<f64> = rows.iter().filter_map(|row| row.item.rtt_avg()).collect();
+    let Some(max) = rtts.iter().copied().reduce(f64::max) else {
+        return Ok(());
+    };
+
+    let width = [
+        1.0, 2.0, 5.0, 10.0, 20.0, 25.0, 50.0, 100.0, 200.0, 250.0, 500.0,
+    ]
+    .into_iter()
+    .find(|w| max / w < 20.0)
+    .unwrap_or(1000.0);
+    let mut buckets = vec![0usize; (max / width) as usize + 1];
+    for rtt in &rtts {
+        buckets[(rtt / width) as usize] += 1;
+    }
+    let tallest = buckets.iter().copied().max().unwrap_or(1);
+
+    let (chart_width, chart_height, bar_width) = (720.0, 200.0, 720.0 / buckets.len() as f64);
+
+    writeln!(html, "<h2>Latency Distribution</h2>")?;
+    writeln!(
+        html,
+        r#"<svg width="{}" height="{}" role="img" aria-label="Histogram of average RTT per node">"#,
+        chart_width,
+        chart_height + 40.0
+    )?;
+    for (i, count) in buckets.iter().enumerate() {
+        let height = *count as f64 / tallest as f64 * chart_height;
+        let x = i as f64 * bar_width;
+        let start = i as f64 * width;
+
+        writeln!(
+            html,
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"><title>{} - {} ms: {} nodes</title></rect>"#,
+            x + 1.0,
+            chart_height - height,
+            bar_width - 2.0,
+            height,
+            rtt_colour(config, start),
+            start,
+            start + width,
+            count
+        )?;
+        if *count > 0 {
+            writeln!(
+                html,
+                r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+                x + bar_width / 2.0,
+                chart_height - height - 4.0,
+                count
+            )?;
+        }
+        writeln!(
+            html,
+            r#"<text x="{:.1}" y="{:.1}">{}</text>"#,
+            x + 1.0,
+            chart_height + 15.0,
+            start
+        )?;
+    }
+    writeln!(
+        html,
+        r#"<text x="{:.1}" y="{:.1}" text-anchor="end">avg RTT (ms)</text>"#,
+        chart_width,
+        chart_height + 35.0
+    )?;
+    writeln!(html, "</svg>")?;
+    Ok(())
+}
+
+fn write_map(html: &mut String, config: &Opts, rows: &[IcmpRow]) -> Result<()> {
+    // Equirectangular projection at two pixels per degree.
+    let project = |lat: f64, lon: f64| ((lon + 180.0) * 2.0, (90.0 - lat) * 2.0);
+
+    writeln!(html, "<h2>Node Locations</h2>")?;
+    writeln!(
+        html,
+        r##"<svg width="720" height="360" viewBox="0 0 720 360" role="img" aria-label="Map of node locations coloured by RTT"><rect width="720" height="360" fill="#f6f8fa" stroke="#d0d7de"/>"##
+    )?;
+    // The same rough land mask the terminal map uses, 5° of latitude a band.
+    let mut land = String::new();
+    for (north, west, east) in land_spans() {
+        let (x, y) = project(north, west);
+        write!(
+            land,
+            "M{x} {y}h{}v10h{}z",
+            (east - west) * 2.0,
+            (west - east) * 2.0
+        )?;
+    }
+    writeln!(html, r##"<path d="{land}" fill="#e6e9ed"/>"##)?;
+
+    for lon in (-150..=150).step_by(30) {
+        let (x, _) = project(0.0, lon as f64);
+        writeln!(
+            html,
+            r##"<line x1="{x}" y1="0" x2="{x}" y2="360" stroke="#d0d7de" stroke-width="{}"/>"##,
+            if lon == 0 { 1.5 } else { 0.5 }
+        )?;
+    }
+    for lat in (-60..=60).step_by(30) {
+        let (_, y) = project(lat as f64, 0.0);
+        writeln!(
+            html,
+            r##"<line x1="0" y1="{y}" x2="720" y2="{y}" stroke="#d0d7de" stroke-width="{}"/>"##,
+            if lat == 0 { 1.5 } else { 0.5 }
+        )?;
+    }
+
+    for row in rows {
+        let Some(node_info) = row.node_info else {
+            continue;
+        };
+        let (x, y) = project(node_info.lat, node_info.lon);
+
+        writeln!(
+            html,
+            r#"<circle cx="{x:.1}" cy="{y:.1}" r="4" fill="{}" fill-opacity="0.8" stroke="white" stroke-width="0.5"><title>{}, {} ({}) to {}: {}</title></circle>"#,
+            row_colour(config, row),
+            escape(&node_info.city),
+            escape(&node_info.country_code),
+            escape(&node_info.isp),
+            escape(&row.item.endpoint),
+            row.item
+                .rtt_avg()
+                .map_or("unreachable".to_string(), |avg| format!("{avg:.1} ms"))
+        )?;
+    }
+    writeln!(html, "</svg>")?;
+
+    let [fast, slow, slower] = config.rtt_thresholds.0;
+    let labels = [
+        format!("<= {fast} ms"),
+        format!("{fast} - {slow} ms"),
+        format!("{slow} - {slower} ms"),
+        format!("> {slower} ms"),
+    ];
+
+    write!(html, r#"<p class="legend">"#)?;
+    for (colour, label) in RTT_COLOURS.iter().zip(labels) {
+        write!(
+            html,
+            r#"<span><i class="swatch" style="background:{colour}"></i>{}</span>"#,
+            escape(&label)
+        )?;
+    }
+    writeln!(
+        html,
+        r#"<span><i class="swatch" style="background:{FAILED_COLOUR}"></i>unreachable</span></p>"#
+    )?;
+    Ok(())
+}
+
+fn write_results(html: &mut String, rows: &[IcmpRow]) -> Result<()> {
+    writeln!(html, "<h2>Results</h2>")?;
+    writeln!(html, r#"<table class="sortable"><thead><tr>"#)?;
+    for (name, sort) in [
+        ("Region", "text"),
+        ("Endpoint", "text"),
+        ("Size", "number"),
+        ("Country", "text"),
+        ("City", "text"),
+        ("ISP", "text"),
+        ("Network", "text"),
+        ("IP", "text"),
+        ("Sent", "number"),
+        ("Recv", "number"),
+        ("Loss", "number"),
+        ("Min", "number"),
+        ("Avg", "number"),
+        ("Max", "number"),
+        ("Std Dev", "number"),
+        ("Error", "text"),
+    ] {
+        write!(html, r#"<th data-sort="{sort}">{name}</th>"#)?;
+    }
+    writeln!(html, "</tr></thead><tbody>")?;
+
+    for row in rows {
+        let node_info = row.node_info;
+        let result = row.item.result.as_ref();
+        let country = node_info.map_or(String::new(), |n| match n.country() {
+            Some(country) => format!("{} {}", country.emoji(), country.iso_short_name()),
+            None => n.country_code.clone(),
+        });
+
+        let text = |value: &str| format!(r#"<td data-value="{0}">{0}</td>"#, escape(value));
+        let number = |value: Option<f64>, display: String| {
+            format!(
+                r#"<td class="num" data-value="{}">{}</td>"#,
+                value.map(|v| v.to_string()).unwrap_or_default(),
+                display
+            )
+        };
+        let ms = |value: Option<f64>| number(value, format_ms(value));
+
+        writeln!(
+            html,
+            r#"<tr{}>{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}</tr>"#,
+            if row.item.rtt_avg().is_none() {
+                r#" class="failed""#
+            } else {
+                ""
+            },
+            text(&row.region().name()),
+            text(&row.item.endpoint),
+            number(Some(row.payload_size as f64), row.payload_size.to_string()),
+            text(&country),
+            text(node_info.map_or("", |n| n.city.as_str())),
+            text(node_info.map_or("", |n| n.isp.as_str())),
+            text(node_info.map_or("", |n| n.network_type())),
+            text(result.map_or("", |r| r.ip_address.as_str())),
+            number(
+                result.map(|r| r.packets_sent),
+                result.map_or(String::new(), |r| r.packets_sent.to_string())
+            ),
+            number(
+                result.map(|r| r.packets_recv),
+                result.map_or(String::new(), |r| r.packets_recv.to_string())
+            ),
+            number(
+                result.map(|r| r.packet_loss),
+                result.map_or(String::new(), |r| format!("{:.1}%", r.packet_loss * 100.0))
+            ),
+            ms(result.map(|r| r.min)),
+            ms(result.map(|r| r.avg)),
+            ms(result.map(|r| r.max)),
+            ms(result.map(|r| r.std_dev)),
+            text(row.item.error.as_deref().unwrap_or("")),
+        )?;
+    }
+
+    writeln!(html, "</tbody></table>")?;
+    Ok(())
+}
+
+fn rtt_colour(config: &Opts, rtt: f64) -> &'static str {
+    RTT_COLOURS[config.rtt_thresholds.band(rtt)]
+}
+
+fn format_ms(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |v| format!("{v:.2} ms"))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
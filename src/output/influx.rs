@@ -0,0 +1,360 @@
+use std::io::Write;
+
+use color_eyre::eyre::{eyre, Context, Result};
+use tracing::info;
+
+use crate::options::InfluxOpts;
+
+use super::{icmp_rows, IcmpRow, RunRecord};
+
+/// How many points are sent to InfluxDB in each write request.
+const BATCH_SIZE: usize = 5000;
+
+/// Formats every ICMP result in the run as InfluxDB line protocol, one point
+/// per endpoint per node.
+fn lines(run: &RunRecord) -> Vec<String> {
+    icmp_rows(&run.responses).map(line).collect()
+}
+
+fn line(row: IcmpRow) -> String {
+    let timestamp = row
+        .response
+        .received_at
+        .timestamp_nanos_opt()
+        .unwrap_or_default();
+
+    let mut tags = vec![
+        ("endpoint", row.item.endpoint.clone()),
+        ("region", row.region().name()),
+    ];
+    if let Some(node_info) = row.node_info {
+        tags.extend([
+            ("country", node_info.country_code.clone()),
+            ("continent", node_info.continent_code.clone()),
+            ("isp", node_info.isp.clone()),
+            ("network_type", node_info.network_type().to_string()),
+        ]);
+    }
+
+    let mut fields = vec![("payload_size", format!("{}i", row.payload_size))];
+    match (&row.item.result, &row.item.error) {
+        (Some(result), None) => fields.extend([
+            ("min", result.min.to_string()),
+            ("avg", result.avg.to_string()),
+            ("max", result.max.to_string()),
+            ("stddev", result.std_dev.to_string()),
+            ("loss", result.packet_loss.to_string()),
+        ]),
+        (_, error) => fields.push((
+            "error",
+            format!(
+                "\"{}\"",
+                escape_string(error.as_deref().unwrap_or("no result returned"))
+            ),
+        )),
+    }
+
+    let tags: String = tags
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(key, value)| format!(",{key}={}", escape_tag(value)))
+        .collect();
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect();
+
+    format!("bping{tags} {} {timestamp}", fields.join(","))
+}
+
+pub fn write(run: &RunRecord, mut writer: impl Write) -> Result<()> {
+    for line in lines(run) {
+        writeln!(writer, "{line}").context("Failed to write InfluxDB output")?;
+    }
+    Ok(())
+}
+
+/// Sends the run to an InfluxDB v2 write endpoint, in batches of points.
+pub async fn push(opts: &InfluxOpts, run: &RunRecord) -> Result<()> {
+    let mut url = reqwest::Url::parse(&opts.url).context("Invalid InfluxDB URL")?;
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    let mut url = url.join("api/v2/write").context("Invalid InfluxDB URL")?;
+    url.query_pairs_mut()
+        .append_pair("org", &opts.org)
+        .append_pair("bucket", &opts.bucket)
+        .append_pair("precision", "ns");
+
+    let client = reqwest::Client::new();
+    let lines = lines(run);
+
+    for batch in lines.chunks(BATCH_SIZE) {
+        info!(points = batch.len(), %url, "Writing points to InfluxDB");
+
+        let mut request = client
+            .post(url.clone())
+            .header("Content-Type", "text/plain; charset=utf-8")
+            .body(batch.join("\n"));
+        if let Some(token) = &opts.token {
+            request = request.header("Authorization", format!("Token {token}"));
+        }
+
+        let response = request
+            .send()
+            .await
+            .context("Failed to send points to InfluxDB")?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(eyre!("InfluxDB rejected the write with {status}: {body}"));
+        }
+    }
+
+    Ok(())
+}
+
+fn escape_tag(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace(',', r"\,")
+        .replace('=', r"\=")
+        .replace(' ', r"\ ")
+}
+
+fn escape_string(value: &str) -> String {
+    value.replace('\\', r"\\").replace('"', r#"\""#)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+    use crate::{
+        job::{JobData, JobResponse},
+        options::EarthRegion,
+    };
+
+    fn response(results: serde_json::Value, isp: &str) -> JobResponse {
+        let response = json!({
+            "nodeInfo": {
+                "operatingSystem": "linux",
+                "isp": isp,
+                "mobile": false,
+                "proxy": false,
+                "residential": true,
+                "regionName": "New South Wales",
+                "countryCode": "AU",
+                "continentCode": "OC",
+                "city": "Sydney",
+                "lat": -33.86,
+                "lon": 151.2,
+            },
+            "results": results,
+        });
+
+        JobResponse {
+            region: EarthRegion::Anywhere,
+            received_at: Utc.timestamp_nanos(1_700_000_000_000_000_000),
+            data: JobData::Icmp {
+                payload_size: 56,
+                response: serde_json::from_value(response).unwrap(),
+            },
+        }
+    }
+
+    fn result(endpoint: &str) -> serde_json::Value {
+        json!({
+            "endpoint": endpoint,
+            "duration": 3000,
+            "result": {
+                "avg": 12.5,
+                "max": 15.0,
+                "min": 10.0,
+                "stdDev": 2.0,
+                "ipAddress": "1.2.3.4",
+                "packetLoss": 0.0,
+                "packetsRecv": 3,
+                "packetsSent": 3,
+                "trips": 3,
+                "attempts": 3,
+            },
+        })
+    }
+
+    fn run(responses: Vec<JobResponse>) -> RunRecord {
+        RunRecord {
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            responses,
+            failures: vec![],
+        }
+    }
+
+    #[test]
+    fn lines_have_tags_fields_and_timestamp() {
+        let lines = lines(&run(vec![response(
+            json!([result("example.com")]),
+            "Telstra",
+        )]));
+
+        assert_eq!(
+            lines,
+            ["bping,endpoint=example.com,region=Anywhere,country=AU,continent=OC,isp=Telstra,network_type=residential payload_size=56i,min=10,avg=12.5,max=15,stddev=2,loss=0 1700000000000000000"]
+        );
+    }
+
+    #[test]
+    fn lines_escape_tag_values() {
+        let lines = lines(&run(vec![response(
+            json!([result("a b,c=d")]),
+            "Foo, Inc. = ISP",
+        )]));
+
+        assert!(lines[0].starts_with(r"bping,endpoint=a\ b\,c\=d,region=Anywhere,"));
+        assert!(lines[0].contains(r",isp=Foo\,\ Inc.\ \=\ ISP,"));
+    }
+
+    #[test]
+    fn lines_quote_and_escape_errors() {
+        let lines = lines(&run(vec![response(
+            json!([{
+                "endpoint": "example.com",
+                "duration": 3000,
+                "error": r#"no route, "code" = 1 \ retry"#,
+            }]),
+            "Telstra",
+        )]));
+
+        assert!(lines[0].contains(r#" payload_size=56i,error="no route, \"code\" = 1 \\ retry" "#));
+    }
+
+    #[test]
+    fn lines_cover_every_result_past_a_batch() {
+        let results: Vec<_> = (0..=BATCH_SIZE)
+            .map(|i| result(&format!("{i}.example.com")))
+            .collect();
+        let lines = lines(&run(vec![response(json!(results), "Telstra")]));
+
+        assert_eq!(lines.len(), BATCH_SIZE + 1);
+        assert_eq!(lines.chunks(BATCH_SIZE).count(), 2);
+    }
+
+    /// Reads one HTTP request, returning its head and body.
+    async fn read_request(stream: &mut tokio::net::TcpStream) -> (String, String) {
+        let mut buffer = vec![];
+        let head_end = loop {
+            let mut chunk = [0; 4096];
+            let read = stream.read(&mut chunk).await.unwrap();
+            assert!(read > 0, "connection closed before the request ended");
+            buffer.extend_from_slice(&chunk[..read]);
+            if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+                break end + 4;
+            }
+        };
+
+        let head = String::from_utf8(buffer[..head_end].to_vec()).unwrap();
+        let length: usize = head
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.eq_ignore_ascii_case("content-length")
+                    .then(|| value.trim().parse().unwrap())
+            })
+            .unwrap_or(0);
+
+        let mut body = buffer[head_end..].to_vec();
+        while body.len() < length {
+            let mut chunk = [0; 65536];
+            let read = stream.read(&mut chunk).await.unwrap();
+            body.extend_from_slice(&chunk[..read]);
+        }
+
+        (head, String::from_utf8(body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn push_writes_batches_with_token() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let mut requests = vec![];
+            while requests.len() < 2 {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                // Keep-alive connections can carry more than one batch.
+                loop {
+                    let request = read_request(&mut stream).await;
+                    requests.push(request);
+                    stream
+                        .write_all(b"HTTP/1.1 204 No Content\r\ncontent-length: 0\r\n\r\n")
+                        .await
+                        .unwrap();
+                    if requests.len() == 2 {
+                        break;
+                    }
+                }
+            }
+            requests
+        });
+
+        let opts = InfluxOpts {
+            url: format!("http://{address}/influx"),
+            org: "my org".to_string(),
+            bucket: "pings".to_string(),
+            token: Some("secret".to_string()),
+        };
+        let results: Vec<_> = (0..=BATCH_SIZE)
+            .map(|i| result(&format!("{i}.example.com")))
+            .collect();
+        let run = run(vec![response(json!(results), "Telstra")]);
+        push(&opts, &run).await.unwrap();
+
+        let requests = server.await.unwrap();
+        let expected = lines(&run);
+        for ((head, body), batch) in requests.iter().zip(expected.chunks(BATCH_SIZE)) {
+            assert!(head.starts_with(
+                "POST /influx/api/v2/write?org=my+org&bucket=pings&precision=ns HTTP/1.1\r\n"
+            ));
+            assert!(head
+                .lines()
+                .any(|line| line.eq_ignore_ascii_case("authorization: Token secret")));
+            assert_eq!(*body, batch.join("\n"));
+        }
+        assert_eq!(requests[0].1.lines().count(), BATCH_SIZE);
+        assert_eq!(requests[1].1.lines().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn push_reports_rejected_writes() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            read_request(&mut stream).await;
+            stream
+                .write_all(b"HTTP/1.1 401 Unauthorized\r\ncontent-length: 12\r\n\r\nbad token!!!")
+                .await
+                .unwrap();
+        });
+
+        let opts = InfluxOpts {
+            url: format!("http://{address}"),
+            org: "org".to_string(),
+            bucket: "pings".to_string(),
+            token: None,
+        };
+        let run = run(vec![response(json!([result("example.com")]), "Telstra")]);
+        let error = push(&opts, &run).await.unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "InfluxDB rejected the write with 401 Unauthorized: bad token!!!"
+        );
+    }
+}
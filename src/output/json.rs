@@ -0,0 +1,40 @@
+use std::io::Write;
+
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::{Context, Result};
+use serde::Serialize;
+
+use crate::{
+    job::JobResponse,
+    options::{JobKind, Opts},
+    stats::RunAggregates,
+};
+
+use super::{JobFailure, RunRecord};
+
+#[derive(Serialize)]
+struct JsonDocument<'a> {
+    parameters: &'a Opts,
+    started_at: DateTime<Utc>,
+    finished_at: DateTime<Utc>,
+    responses: &'a [JobResponse],
+    failures: &'a [JobFailure],
+    aggregates: Option<RunAggregates>,
+}
+
+pub fn write(config: &Opts, run: &RunRecord, mut writer: impl Write) -> Result<()> {
+    let document = JsonDocument {
+        parameters: config,
+        started_at: run.started_at,
+        finished_at: run.finished_at,
+        responses: &run.responses,
+        failures: &run.failures,
+        aggregates: matches!(config.job, JobKind::Icmp(_))
+            .then(|| RunAggregates::from_responses(&run.responses)),
+    };
+
+    serde_json::to_writer_pretty(&mut writer, &document).context("Failed to write JSON output")?;
+    writeln!(writer)?;
+
+    Ok(())
+}
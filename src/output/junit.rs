@@ -0,0 +1,181 @@
+use std::{fmt::Write as _, io::Write};
+
+use color_eyre::eyre::{Context, Result};
+
+use crate::{
+    models::{types::PerformIcmpResponseResultsItem, NodeInfo},
+    options::{JobKind, Opts},
+};
+
+use super::{icmp_rows, RunRecord};
+
+/// A test case for one endpoint pinged from one requested region.
+struct TestCase<'a> {
+    endpoint: &'a str,
+    region: String,
+    time_secs: f64,
+    failures: Vec<String>,
+    output: String,
+}
+
+pub fn write(config: &Opts, run: &RunRecord, mut writer: impl Write) -> Result<()> {
+    let JobKind::Icmp(ref opts) = config.job else {
+        return Ok(());
+    };
+
+    let mut cases = vec![];
+    for endpoint in &opts.endpoints {
+        for region in &config.regions {
+            let region = region.name();
+            let mut case = TestCase {
+                endpoint,
+                region: region.clone(),
+                time_secs: 0.0,
+                failures: vec![],
+                output: String::new(),
+            };
+
+            let rows = icmp_rows(&run.responses)
+                .filter(|row| row.region().name() == region && row.item.endpoint == *endpoint);
+
+            for row in rows {
+                case.record(config, row.node_info, row.item);
+            }
+
+            if case.output.is_empty() {
+                case.failures
+                    .push("No node returned a result for this region".to_string());
+            }
+            cases.push(case);
+        }
+    }
+
+    let failures = cases.iter().filter(|c| !c.failures.is_empty()).count();
+    let time_secs = (run.finished_at - run.started_at).num_milliseconds() as f64 / 1000.0;
+
+    let mut xml = String::new();
+    writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        xml,
+        r#"<testsuites name="bping" tests="{}" failures="{}" time="{:.3}">"#,
+        cases.len(),
+        failures,
+        time_secs
+    )?;
+    writeln!(
+        xml,
+        r#"  <testsuite name="bping" tests="{}" failures="{}" errors="0" time="{:.3}" timestamp="{}">"#,
+        cases.len(),
+        failures,
+        time_secs,
+        run.started_at.format("%Y-%m-%dT%H:%M:%S")
+    )?;
+
+    for case in &cases {
+        writeln!(
+            xml,
+            r#"    <testcase classname="{}" name="{}" time="{:.3}">"#,
+            escape(case.endpoint),
+            escape(&case.region),
+            case.time_secs
+        )?;
+        if let Some(first) = case.failures.first() {
+            writeln!(
+                xml,
+                r#"      <failure message="{}">{}</failure>"#,
+                escape(first),
+                escape(&case.failures.join("\n"))
+            )?;
+        }
+        if !case.output.is_empty() {
+            writeln!(
+                xml,
+                "      <system-out>{}</system-out>",
+                escape(&case.output)
+            )?;
+        }
+        writeln!(xml, "    </testcase>")?;
+    }
+
+    writeln!(xml, "  </testsuite>")?;
+    writeln!(xml, "</testsuites>")?;
+
+    writer
+        .write_all(xml.as_bytes())
+        .context("Failed to write JUnit report")?;
+    Ok(())
+}
+
+impl TestCase<'_> {
+    /// Adds a node's result to the system-out, failing the case if the node
+    /// errored, lost every packet or breached one of the thresholds.
+    fn record(
+        &mut self,
+        config: &Opts,
+        node_info: Option<&NodeInfo>,
+        item: &PerformIcmpResponseResultsItem,
+    ) {
+        let node = match node_info {
+            Some(n) => format!(
+                "{} {}, {} ({}, {})",
+                n.country_code, n.city, n.region_name, n.isp, n.operating_system
+            ),
+            None => "Unknown node".to_string(),
+        };
+
+        if let Some(duration) = item.duration {
+            self.time_secs = self.time_secs.max(duration / 1000.0);
+        }
+
+        let Some(result) = item.result.as_ref().filter(|_| item.error.is_none()) else {
+            let error = item.error.as_deref().unwrap_or("no result returned");
+            let _ = writeln!(self.output, "{node}: error: {error}");
+            self.failures
+                .push(format!("{node} returned an error: {error}"));
+            return;
+        };
+
+        let _ = writeln!(
+            self.output,
+            "{node}: {} ({}) {}/{} packets, {:.1}% loss, rtt min/avg/max/mdev = {:.3}/{:.3}/{:.3}/{:.3} ms",
+            self.endpoint,
+            result.ip_address,
+            result.packets_recv,
+            result.packets_sent,
+            result.packet_loss * 100.0,
+            result.min,
+            result.avg,
+            result.max,
+            result.std_dev
+        );
+
+        if result.packet_loss >= 1.0 {
+            self.failures.push(format!("{node} lost every packet"));
+            return;
+        }
+        if let Some(max_loss) = config.max_loss {
+            if result.packet_loss * 100.0 > max_loss {
+                self.failures.push(format!(
+                    "{node} lost {:.1}% of packets, above the {max_loss}% threshold",
+                    result.packet_loss * 100.0
+                ));
+            }
+        }
+        if let Some(max_rtt) = config.max_rtt {
+            if result.avg > max_rtt {
+                self.failures.push(format!(
+                    "{node} averaged {:.3} ms, above the {max_rtt} ms threshold",
+                    result.avg
+                ));
+            }
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
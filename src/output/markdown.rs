@@ -0,0 +1,112 @@
+use std::io::Write;
+
+use color_eyre::eyre::{Context, Result};
+
+use crate::{
+    options::{EarthRegion, JobKind, Opts},
+    stats::{IcmpAggregates, RunAggregates},
+};
+
+use super::{icmp_rows, RunRecord};
+
+/// Writes a GitHub flavoured markdown table with a row per node result,
+/// followed by the aggregates of each endpoint.
+pub fn write(config: &Opts, run: &RunRecord, mut writer: impl Write) -> Result<()> {
+    let JobKind::Icmp(ref opts) = config.job else {
+        return Ok(());
+    };
+    let multiple_endpoints = opts.endpoints.len() > 1;
+    let regions: Vec<String> = config.regions.iter().map(EarthRegion::name).collect();
+
+    let mut md = String::new();
+    md.push_str(&format!(
+        "**bping {}** from {}\n\n",
+        escape(&opts.endpoints.join(", ")),
+        escape(&regions.join(", "))
+    ));
+
+    if multiple_endpoints {
+        md.push_str("| Endpoint | | Country | ISP | Avg RTT | Loss |\n");
+        md.push_str("| --- | --- | --- | --- | ---: | ---: |\n");
+    } else {
+        md.push_str("| | Country | ISP | Avg RTT | Loss |\n");
+        md.push_str("| --- | --- | --- | ---: | ---: |\n");
+    }
+
+    for row in icmp_rows(&run.responses) {
+        let (item, node_info) = (row.item, row.node_info);
+        let country = node_info.and_then(|n| n.country());
+
+        let (avg, loss) = match (&item.result, &item.error) {
+            (Some(result), None) if result.packet_loss >= 1.0 => {
+                ("-".to_string(), "**100%**".to_string())
+            }
+            (Some(result), None) => (
+                format!("{:.1} ms", result.avg),
+                format!("{:.0}%", result.packet_loss * 100.0),
+            ),
+            (_, error) => (
+                "-".to_string(),
+                format!(
+                    "**error**: {}",
+                    escape(error.as_deref().unwrap_or("no result"))
+                ),
+            ),
+        };
+
+        if multiple_endpoints {
+            md.push_str(&format!("| {} ", escape(&item.endpoint)));
+        }
+        md.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            country.as_ref().map(|c| c.emoji()).unwrap_or_default(),
+            escape(&match (&country, node_info) {
+                (Some(country), _) => country.iso_short_name().to_string(),
+                (None, Some(n)) => n.country_code.clone(),
+                (None, None) => "Unknown".to_string(),
+            }),
+            escape(node_info.map_or("", |n| n.isp.as_str())),
+            avg,
+            loss
+        ));
+    }
+
+    md.push('\n');
+    let aggregates = RunAggregates::from_responses(&run.responses);
+    for (endpoint, aggregates) in &aggregates.endpoints {
+        md.push_str(&format!(
+            "- **{}**: {}\n",
+            escape(endpoint),
+            summary(aggregates)
+        ));
+    }
+
+    writer
+        .write_all(md.as_bytes())
+        .context("Failed to write markdown output")?;
+    Ok(())
+}
+
+fn summary(a: &IcmpAggregates) -> String {
+    let mut summary = format!("{}/{} nodes reachable", a.successful, a.results);
+    if let (Some(min), Some(avg), Some(max)) = (a.rtt_min, a.rtt_avg, a.rtt_max) {
+        summary.push_str(&format!(", avg {avg:.1} ms (min {min:.1}, max {max:.1})"));
+    }
+    if let Some(loss) = a.packet_loss {
+        summary.push_str(&format!(", {:.1}% packet loss", loss * 100.0));
+    }
+    if a.errors > 0 {
+        summary.push_str(&format!(", {} errors", a.errors));
+    }
+    summary
+}
+
+/// Escapes the characters that would break out of a table cell or start
+/// formatting.
+fn escape(text: &str) -> String {
+    text.replace('\\', r"\\")
+        .replace('|', r"\|")
+        .replace('*', r"\*")
+        .replace('_', r"\_")
+        .replace('\n', " ")
+}
@@ -0,0 +1,145 @@
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::{Context, Result};
+use serde::Serialize;
+use std::fs::File;
+
+use crate::{
+    job::{JobData, JobResponse},
+    models::{types::PerformIcmpResponseResultsItem, NodeInfo},
+    options::{EarthRegion, Opts, OutputFormat},
+};
+
+mod csv;
+mod geojson;
+mod html;
+mod influx;
+mod json;
+mod junit;
+mod markdown;
+mod ndjson;
+mod prometheus;
+
+/// Everything that came back from a run, kept so it can be written out in a
+/// machine readable format once every job has finished.
+#[derive(Debug)]
+pub struct RunRecord {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub responses: Vec<JobResponse>,
+    pub failures: Vec<JobFailure>,
+}
+
+/// A job that never got a response, eg. because no node was available in
+/// its region.
+#[derive(Debug, Serialize)]
+pub struct JobFailure {
+    pub region: EarthRegion,
+    pub received_at: DateTime<Utc>,
+    pub error: String,
+}
+
+/// A single endpoint pinged by a single node, the unit every output format
+/// lays ICMP results out in.
+#[derive(Clone, Copy)]
+pub struct IcmpRow<'a> {
+    pub response: &'a JobResponse,
+    pub payload_size: usize,
+    pub node_info: Option<&'a NodeInfo>,
+    pub item: &'a PerformIcmpResponseResultsItem,
+}
+
+impl<'a> IcmpRow<'a> {
+    /// The region the job was requested from.
+    pub fn region(&self) -> &'a EarthRegion {
+        &self.response.region
+    }
+}
+
+/// Flattens responses into a row for every endpoint each node pinged,
+/// skipping anything but ICMP responses.
+pub fn icmp_rows<'a>(
+    responses: impl IntoIterator<Item = &'a JobResponse>,
+) -> impl Iterator<Item = IcmpRow<'a>> {
+    responses
+        .into_iter()
+        .filter_map(|response| match &response.data {
+            JobData::Icmp {
+                payload_size,
+                response: icmp,
+            } => Some((response, *payload_size, icmp)),
+            _ => None,
+        })
+        .flat_map(|(response, payload_size, icmp)| {
+            icmp.results.iter().map(move |item| IcmpRow {
+                response,
+                payload_size,
+                node_info: icmp.node_info.as_ref(),
+                item,
+            })
+        })
+}
+
+/// Writes a finished run to stdout in the configured output format, and to any
+/// report files that were asked for.
+pub fn write_run(config: &'static Opts, run: &RunRecord) -> Result<()> {
+    match config.output {
+        OutputFormat::Text | OutputFormat::Ndjson => {}
+        OutputFormat::Json => json::write(config, run, std::io::stdout().lock())?,
+        OutputFormat::Csv => csv::write(run, std::io::stdout().lock())?,
+        OutputFormat::Prometheus => prometheus::write(run, std::io::stdout().lock())?,
+        OutputFormat::Influx => influx::write(run, std::io::stdout().lock())?,
+        OutputFormat::Geojson => geojson::write(run, std::io::stdout().lock())?,
+        OutputFormat::Markdown => markdown::write(config, run, std::io::stdout().lock())?,
+    }
+
+    if let Some(path) = &config.csv_file {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create CSV file {}", path.display()))?;
+        csv::write(run, file)?;
+    }
+
+    if let Some(path) = &config.junit_file {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create JUnit report {}", path.display()))?;
+        junit::write(config, run, file)?;
+    }
+
+    if let Some(path) = &config.html_file {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create HTML report {}", path.display()))?;
+        html::write(config, run, file)?;
+    }
+
+    if let Some(path) = &config.prometheus_file {
+        prometheus::write_file(run, path)?;
+    }
+
+    Ok(())
+}
+
+/// Pushes a finished run to the services it was asked to be sent to.
+pub async fn push_run(config: &'static Opts, run: &RunRecord) -> Result<()> {
+    if let Some(influx) = &config.influx {
+        influx::push(influx, run).await?;
+    }
+
+    Ok(())
+}
+
+/// Writes a single response to stdout as soon as it lands, for the streaming
+/// output formats.
+pub fn write_response(config: &'static Opts, response: &JobResponse) -> Result<()> {
+    match config.output {
+        OutputFormat::Ndjson => ndjson::write(response, std::io::stdout().lock()),
+        _ => Ok(()),
+    }
+}
+
+/// Writes a job that got no response to stdout as soon as it fails, so the
+/// streaming output formats can tell it apart from a region yet to answer.
+pub fn write_failure(config: &'static Opts, failure: &JobFailure) -> Result<()> {
+    match config.output {
+        OutputFormat::Ndjson => ndjson::write_failure(failure, std::io::stdout().lock()),
+        _ => Ok(()),
+    }
+}
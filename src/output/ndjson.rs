@@ -0,0 +1,110 @@
+use std::io::Write;
+
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::{Context, Result};
+use serde::Serialize;
+
+use crate::{
+    job::{JobData, JobResponse},
+    models::{
+        types::{
+            PerformDnsResponseResultsItem, PerformHlsResponseResultsItem,
+            PerformHttpResponseResultsItem, PerformIcmpResponseResultsItem,
+        },
+        NodeInfo,
+    },
+    options::{EarthRegion, NodeIdentity},
+};
+
+use super::{icmp_rows, JobFailure};
+
+/// One line of NDJSON output, for every endpoint each node probed.
+#[derive(Serialize)]
+struct NodeResultLine<'a> {
+    timestamp: DateTime<Utc>,
+    region: &'a EarthRegion,
+    #[serde(flatten)]
+    result: NodeResult<'a>,
+    node_info: Option<&'a NodeInfo>,
+    /// The identity `--node` takes to send jobs back to the same node.
+    node: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum NodeResult<'a> {
+    Icmp {
+        payload_size: usize,
+        #[serde(flatten)]
+        item: &'a PerformIcmpResponseResultsItem,
+    },
+    Dns(&'a PerformDnsResponseResultsItem),
+    Http(&'a PerformHttpResponseResultsItem),
+    Hls(&'a PerformHlsResponseResultsItem),
+}
+
+/// A job that got no response, eg. because no node was available in its
+/// region.
+#[derive(Serialize)]
+#[serde(tag = "type", rename = "error")]
+struct FailureLine<'a> {
+    #[serde(flatten)]
+    failure: &'a JobFailure,
+}
+
+/// Writes one line per node result in the response and flushes straight away,
+/// so whatever is reading the output sees results as they land.
+pub fn write(response: &JobResponse, mut writer: impl Write) -> Result<()> {
+    let (results, node_info): (Vec<NodeResult>, Option<NodeInfo>) = match &response.data {
+        JobData::Icmp { response: icmp, .. } => (
+            icmp_rows([response])
+                .map(|row| NodeResult::Icmp {
+                    payload_size: row.payload_size,
+                    item: row.item,
+                })
+                .collect(),
+            icmp.node_info.clone(),
+        ),
+        JobData::Dns(response) => (
+            response.results.iter().map(NodeResult::Dns).collect(),
+            response.node_info.clone().map(Into::into),
+        ),
+        JobData::Http(response) => (
+            response.results.iter().map(NodeResult::Http).collect(),
+            response.node_info.clone().map(Into::into),
+        ),
+        JobData::Hls(response) => (
+            response.results.iter().map(NodeResult::Hls).collect(),
+            response.node_info.clone().map(Into::into),
+        ),
+    };
+
+    for result in results {
+        let line = NodeResultLine {
+            timestamp: response.received_at,
+            region: &response.region,
+            result,
+            node_info: node_info.as_ref(),
+            node: node_info
+                .as_ref()
+                .map(|node| NodeIdentity::from(node).to_string()),
+        };
+
+        serde_json::to_writer(&mut writer, &line).context("Failed to write NDJSON output")?;
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Writes a line for a job that failed, flushed like the node results so a
+/// failed region can be told apart from one that hasn't answered yet.
+pub fn write_failure(failure: &JobFailure, mut writer: impl Write) -> Result<()> {
+    serde_json::to_writer(&mut writer, &FailureLine { failure })
+        .context("Failed to write NDJSON output")?;
+    writeln!(writer)?;
+    writer.flush()?;
+
+    Ok(())
+}
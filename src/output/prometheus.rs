@@ -0,0 +1,133 @@
+use std::{
+    collections::BTreeMap,
+    ffi::OsString,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::{Context, Result};
+
+use crate::{models::types::PerformIcmpResponseResultsItem, stats::IcmpAggregates};
+
+use super::{icmp_rows, RunRecord};
+
+/// The labels of a series. Nodes sharing the same labels are aggregated into
+/// one sample, as OpenMetrics does not allow duplicate series.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Labels<'a> {
+    endpoint: &'a str,
+    region: String,
+    country: &'a str,
+    continent: &'a str,
+    isp: &'a str,
+    mobile: Option<bool>,
+    residential: Option<bool>,
+}
+
+impl std::fmt::Display for Labels<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let flag = |v: Option<bool>| v.map(|v| v.to_string()).unwrap_or_default();
+
+        write!(
+            f,
+            r#"endpoint="{}",region="{}",country="{}",continent="{}",isp="{}",mobile="{}",residential="{}""#,
+            escape(self.endpoint),
+            escape(&self.region),
+            escape(self.country),
+            escape(self.continent),
+            escape(self.isp),
+            flag(self.mobile),
+            flag(self.residential)
+        )
+    }
+}
+
+pub fn write(run: &RunRecord, mut writer: impl Write) -> Result<()> {
+    let mut series: BTreeMap<Labels, Vec<&PerformIcmpResponseResultsItem>> = BTreeMap::new();
+
+    for row in icmp_rows(&run.responses) {
+        let node_info = row.node_info;
+        let labels = Labels {
+            endpoint: &row.item.endpoint,
+            region: row.region().name(),
+            country: node_info.map_or("", |n| n.country_code.as_str()),
+            continent: node_info.map_or("", |n| n.continent_code.as_str()),
+            isp: node_info.map_or("", |n| n.isp.as_str()),
+            mobile: node_info.map(|n| n.mobile),
+            residential: node_info.map(|n| n.residential),
+        };
+        series.entry(labels).or_default().push(row.item);
+    }
+
+    let aggregates: Vec<(Labels, IcmpAggregates)> = series
+        .into_iter()
+        .map(|(labels, items)| (labels, IcmpAggregates::from_results(items)))
+        .collect();
+
+    let mut metrics = String::new();
+    let mut gauge = |name: &str, help: &str, value: fn(&IcmpAggregates) -> Option<f64>| {
+        metrics.push_str(&format!("# HELP {name} {help}\n# TYPE {name} gauge\n"));
+        for (labels, aggregates) in &aggregates {
+            if let Some(value) = value(aggregates) {
+                metrics.push_str(&format!("{name}{{{labels}}} {value}\n"));
+            }
+        }
+    };
+
+    gauge(
+        "bping_rtt_avg_ms",
+        "Average round trip time in milliseconds of the nodes that received a reply.",
+        |a| a.rtt_avg,
+    );
+    gauge(
+        "bping_rtt_min_ms",
+        "Minimum round trip time in milliseconds.",
+        |a| a.rtt_min,
+    );
+    gauge(
+        "bping_rtt_max_ms",
+        "Maximum round trip time in milliseconds.",
+        |a| a.rtt_max,
+    );
+    gauge(
+        "bping_packet_loss_ratio",
+        "Ratio of packets sent that were lost, from 0 to 1.",
+        |a| a.packet_loss,
+    );
+    gauge(
+        "bping_probe_success",
+        "Whether every node received a reply without errors.",
+        |a| Some(if a.failed + a.errors == 0 { 1.0 } else { 0.0 }),
+    );
+    metrics.push_str("# EOF\n");
+
+    writer
+        .write_all(metrics.as_bytes())
+        .context("Failed to write Prometheus output")?;
+    Ok(())
+}
+
+/// Writes the gauges to `<path>.tmp` and renames it over `path`, so a textfile
+/// collector scraping the directory only ever sees a whole file.
+pub fn write_file(run: &RunRecord, path: &Path) -> Result<()> {
+    let mut tmp = OsString::from(path);
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let mut file = File::create(&tmp)
+        .with_context(|| format!("Failed to create Prometheus file {}", tmp.display()))?;
+    write(run, &mut file)?;
+    file.sync_all()
+        .with_context(|| format!("Failed to write Prometheus file {}", tmp.display()))?;
+
+    std::fs::rename(&tmp, path)
+        .with_context(|| format!("Failed to move {} to {}", tmp.display(), path.display()))
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('\n', r"\n")
+}
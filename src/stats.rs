@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{
+    job::{JobData, JobResponse},
+    models::types::PerformIcmpResponseResultsItem,
+};
+
+/// Aggregates over a set of ICMP results, one result being one endpoint pinged
+/// by one node.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IcmpAggregates {
+    pub results: usize,
+    pub successful: usize,
+    pub failed: usize,
+    pub errors: usize,
+    pub packets_sent: f64,
+    pub packets_recv: f64,
+    pub packet_loss: Option<f64>,
+    pub rtt_min: Option<f64>,
+    pub rtt_avg: Option<f64>,
+    pub rtt_max: Option<f64>,
+}
+
+/// Aggregates for a whole run, overall and for each endpoint.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RunAggregates {
+    pub overall: IcmpAggregates,
+    pub endpoints: BTreeMap<String, IcmpAggregates>,
+}
+
+impl IcmpAggregates {
+    pub fn from_results<'a>(
+        results: impl IntoIterator<Item = &'a PerformIcmpResponseResultsItem>,
+    ) -> Self {
+        let mut aggregates = Self::default();
+        let mut rtt_total = 0.0;
+
+        for item in results {
+            aggregates.results += 1;
+
+            let Some(result) = item.result.as_ref().filter(|_| item.error.is_none()) else {
+                aggregates.errors += 1;
+                continue;
+            };
+
+            aggregates.packets_sent += result.packets_sent;
+            aggregates.packets_recv += result.packets_recv;
+
+            if result.packet_loss >= 1.0 {
+                aggregates.failed += 1;
+                continue;
+            }
+
+            aggregates.successful += 1;
+            rtt_total += result.avg;
+            aggregates.rtt_min = Some(aggregates.rtt_min.map_or(result.min, |m| m.min(result.min)));
+            aggregates.rtt_max = Some(aggregates.rtt_max.map_or(result.max, |m| m.max(result.max)));
+        }
+
+        if aggregates.packets_sent > 0.0 {
+            aggregates.packet_loss = Some(1.0 - aggregates.packets_recv / aggregates.packets_sent);
+        }
+        if aggregates.successful > 0 {
+            aggregates.rtt_avg = Some(rtt_total / aggregates.successful as f64);
+        }
+
+        aggregates
+    }
+}
+
+impl RunAggregates {
+    /// Aggregates the ICMP results of a run.
+    pub fn from_responses(responses: &[JobResponse]) -> Self {
+        let results: Vec<&PerformIcmpResponseResultsItem> = icmp_results(responses).collect();
+
+        let mut by_endpoint: BTreeMap<String, Vec<&PerformIcmpResponseResultsItem>> =
+            BTreeMap::new();
+        for result in &results {
+            by_endpoint
+                .entry(result.endpoint.clone())
+                .or_default()
+                .push(result);
+        }
+
+        Self {
+            overall: IcmpAggregates::from_results(results),
+            endpoints: by_endpoint
+                .into_iter()
+                .map(|(endpoint, results)| (endpoint, IcmpAggregates::from_results(results)))
+                .collect(),
+        }
+    }
+}
+
+/// Every ICMP result in a run, across all nodes.
+pub fn icmp_results(
+    responses: &[JobResponse],
+) -> impl Iterator<Item = &PerformIcmpResponseResultsItem> {
+    responses.iter().flat_map(|r| match &r.data {
+        JobData::Icmp { response, .. } => response.results.as_slice(),
+        _ => &[],
+    })
+}
+
+/// Statistics over the nodes in one group of the end of run summary. The RTT
+/// percentiles are taken over each node's average RTT.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GroupSummary {
+    pub nodes: usize,
+    pub successful: usize,
+    pub rtt_min: Option<f64>,
+    pub rtt_p50: Option<f64>,
+    pub rtt_p95: Option<f64>,
+    pub rtt_max: Option<f64>,
+    pub jitter: Option<f64>,
+    pub packet_loss: Option<f64>,
+}
+
+impl GroupSummary {
+    pub fn from_results<'a>(
+        results: impl IntoIterator<Item = &'a PerformIcmpResponseResultsItem>,
+    ) -> Self {
+        let mut summary = Self::default();
+        let mut rtts = vec![];
+        let mut jitters = vec![];
+        let mut losses = vec![];
+
+        for item in results {
+            summary.nodes += 1;
+
+            let Some(result) = item.result.as_ref().filter(|_| item.error.is_none()) else {
+                continue;
+            };
+            losses.push(result.packet_loss);
+
+            if result.packet_loss < 1.0 {
+                summary.successful += 1;
+                rtts.push(result.avg);
+                jitters.push(result.std_dev);
+            }
+        }
+
+        rtts.sort_by(f64::total_cmp);
+        summary.rtt_min = rtts.first().copied();
+        summary.rtt_p50 = percentile(&rtts, 50.0);
+        summary.rtt_p95 = percentile(&rtts, 95.0);
+        summary.rtt_max = rtts.last().copied();
+        summary.jitter = mean(&jitters);
+        summary.packet_loss = mean(&losses);
+
+        summary
+    }
+
+    pub fn success_rate(&self) -> f64 {
+        if self.nodes == 0 {
+            return 0.0;
+        }
+        self.successful as f64 / self.nodes as f64
+    }
+}
+
+/// The nearest-rank percentile of sorted values.
+fn percentile(sorted: &[f64], percentile: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (percentile / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.saturating_sub(1)).copied()
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}